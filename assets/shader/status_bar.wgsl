@@ -6,12 +6,14 @@ const COLOR_VOID = vec4<f32>(0.0, 0.0, 0.0, 0.333333);
 const COLOR_STAMINA = vec4<f32>(0.8, 0.8, 0.8, 0.4);
 const COLOR_HEALTH = vec4<f32>(1.0, 0.0, 0.0, 0.6);
 const COLOR_AMMO = vec4<f32>(0.8, 0.8, 0.8, 0.4);
+const COLOR_RESERVE = vec4<f32>(0.8, 0.8, 0.8, 0.25);
 
 struct Material {
     health: f32,
     health_alpha: f32,
     ammo: f32,
     ammo_alpha: f32,
+    reserve: f32,
     stamina: f32,
 };
 
@@ -63,5 +65,6 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let stamina = bar(material.stamina, center, mix_alpha(COLOR_STAMINA, 1.0                  ), 0.62, 0.01);
     let health  = bar(material.health , center, mix_alpha(COLOR_HEALTH , material.health_alpha), 0.91, 0.06);
     let ammo    = bar(material.ammo   , center, mix_alpha(COLOR_AMMO   , material.ammo_alpha  ), 1.00, 0.01);
-    return vec4<f32>(stamina + health + ammo);
+    let reserve = bar(material.reserve, center, mix_alpha(COLOR_RESERVE, material.ammo_alpha  ), 0.96, 0.01);
+    return vec4<f32>(stamina + health + ammo + reserve);
 }
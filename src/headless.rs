@@ -0,0 +1,165 @@
+use crate::{
+    plugin::{
+        bot::BotPlugin,
+        collision::CollisionPlugin,
+        kinetics::KineticsPlugin,
+        navigation::NavigationPlugin,
+        scenario::{Scenario, ScenarioPlugin},
+        simulation::{SimulationPlugin, TIMESTEP},
+        ActorPlugin, AudioPlugin, BloodPlugin, BonusPlugin, CrosshairPlugin, ExplosionPlugin,
+        HealthPlugin, LoadingPlugin, MeleePlugin, ProjectilePlugin, StatusBarPlugin, TileMap,
+        WeaponPlugin,
+    },
+    resource::{AssetStorage, RngResource, Settings},
+    state::AppState,
+};
+use bevy::{
+    asset::{AssetApp, AssetPlugin},
+    audio::AudioSource,
+    image::Image,
+    mesh::Mesh,
+    prelude::{App, AppExtStates, MinimalPlugins},
+    sprite_render::ColorMaterial,
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+};
+
+/// Builds an app which runs the game logic without a window, GPU and audio. Rendering related
+/// assets are only registered, so the game code which creates them keeps working, but nothing is
+/// drawn
+pub fn build(settings: Settings, scenario: Scenario) -> App {
+    let mut rng = RngResource::new_or_random(settings.game.seed);
+    let mut app = App::new();
+
+    // every update advances the time by exactly one simulation step regardless of how long the
+    // update actually took, so the simulation runs as fast as possible
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AssetPlugin::default())
+        .add_plugins(StatesPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TIMESTEP))
+        .init_asset::<AudioSource>()
+        .init_asset::<ColorMaterial>()
+        .init_asset::<Image>()
+        .init_asset::<Mesh>();
+
+    app.add_plugins(ActorPlugin)
+        .add_plugins(AudioPlugin::new(settings.audio.sources, rng.fork()))
+        .add_plugins(BloodPlugin)
+        .add_plugins(BonusPlugin)
+        .add_plugins(BotPlugin)
+        .add_plugins(CollisionPlugin)
+        .add_plugins(CrosshairPlugin)
+        .add_plugins(ExplosionPlugin)
+        .add_plugins(HealthPlugin)
+        .add_plugins(KineticsPlugin)
+        .add_plugins(LoadingPlugin)
+        .add_plugins(MeleePlugin)
+        .add_plugins(NavigationPlugin)
+        .add_plugins(ProjectilePlugin)
+        .add_plugins(ScenarioPlugin)
+        .add_plugins(SimulationPlugin)
+        .add_plugins(StatusBarPlugin)
+        .add_plugins(WeaponPlugin)
+        .init_state::<AppState>()
+        .insert_resource(AssetStorage::default())
+        .insert_resource(rng)
+        .insert_resource(TileMap::default())
+        .insert_resource(scenario)
+        .insert_resource(settings);
+
+    return app;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugin::{player::Player, scenario::WavesScenario, Actor, ActorKind, Health},
+        resource::GameSettings,
+    };
+    use bevy::{
+        ecs::query::With,
+        prelude::{State, Transform, World},
+    };
+
+    const FRAMES: usize = 60 * 60 * 2;
+    const SEED: u64 = 42;
+
+    fn run(seed: u64, frames: usize) -> App {
+        return run_with(seed, 1, frames);
+    }
+
+    fn run_with(seed: u64, players: u8, frames: usize) -> App {
+        let settings = Settings {
+            game: GameSettings {
+                seed,
+                players,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut app = build(settings, Scenario::new(WavesScenario::new("default", 1)));
+
+        for _ in 0..frames {
+            app.update();
+        }
+
+        return app;
+    }
+
+    fn count_actors(world: &mut World, kind: ActorKind) -> usize {
+        return world
+            .query::<&Actor>()
+            .iter(world)
+            .filter(|a| a.config.kind == kind)
+            .count();
+    }
+
+    fn snapshot(world: &mut World) -> Vec<(Transform, f32)> {
+        return world
+            .query_filtered::<(&Transform, &Health), With<Actor>>()
+            .iter(world)
+            .map(|(t, h)| (*t, h.get()))
+            .collect();
+    }
+
+    #[test]
+    fn waves() {
+        let mut app = run(SEED, FRAMES);
+        let world = app.world_mut();
+        assert_eq!(world.resource::<State<AppState>>().get(), &AppState::Game);
+
+        // standing still, the player must be killed by zombies of the first wave eventually
+        let players = world
+            .query_filtered::<(), With<Player>>()
+            .iter(world)
+            .count();
+        assert_eq!(players, 0);
+        assert!(count_actors(world, ActorKind::Zombie) > 0);
+    }
+
+    #[test]
+    fn coop() {
+        let mut app = run_with(SEED, 2, 600);
+        let world = app.world_mut();
+        let mut indexes = world
+            .query::<&Player>()
+            .iter(world)
+            .map(|p| p.index)
+            .collect::<Vec<_>>();
+
+        indexes.sort_unstable();
+        assert_eq!(indexes, vec![0, 1]);
+    }
+
+    #[test]
+    fn determinism() {
+        let mut app_1 = run(SEED, FRAMES / 2);
+        let mut app_2 = run(SEED, FRAMES / 2);
+        let snapshot_1 = snapshot(app_1.world_mut());
+        let snapshot_2 = snapshot(app_2.world_mut());
+        assert!(snapshot_1.len() > 1);
+        assert_eq!(snapshot_1, snapshot_2);
+    }
+}
@@ -1,4 +1,5 @@
 mod data;
+mod headless;
 mod map;
 mod plugin;
 mod resource;
@@ -13,21 +14,27 @@ use crate::{
         collision::CollisionPlugin,
         debug::DebugPlugin,
         kinetics::KineticsPlugin,
+        navigation::NavigationPlugin,
+        net::{NetPlugin, DEFAULT_PORT},
         player::PlayerPlugin,
+        replay::{Replay, ReplayPlugin},
+        save::{SaveGame, SavePlugin},
         scenario::{
-            BenchProjectilesScenario, BenchZombiesScenario, Scenario, ScenarioPlugin,
-            TestBotSpreadScenario, TestScenario, WavesScenario,
+            BenchProjectilesScenario, BenchZombiesScenario, RemoteScenario, Scenario,
+            ScenarioPlugin, TestBotSpreadScenario, TestScenario, WavesScenario,
         },
+        simulation::SimulationPlugin,
         ActorPlugin, AmbienceFxPlugin, AudioPlugin, BloodPlugin, BonusPlugin, BreathPlugin,
         CrosshairPlugin, CursorGrab, DebugTweaksPlugin, ExplosionPlugin, FootstepsPlugin,
         HealthPlugin, HeartbeatPlugin, InputPlugin, LoadingPlugin, MainCamera, MeleePlugin,
-        ParticlePlugin, ProjectilePlugin, SkipLoaderPlugin, StatusBarPlugin, TerrainPlugin,
-        TileMapPlugin, UiNotificationPlugin, WeaponPlugin,
+        ParticlePlugin, ProjectilePlugin, SkipLoaderPlugin, Smoothing, SmoothingPlugin,
+        StatusBarPlugin, TerrainPlugin, TileMapPlugin, UiNotificationPlugin, WeaponPlugin,
     },
-    resource::{AssetStorage, ScenarioSettings, Settings},
+    resource::{AssetStorage, RngResource, ScenarioSettings, Settings},
     state::AppState,
     util::ext::AppExt,
 };
+use anyhow::{ensure, Result};
 use bevy::{
     camera::Camera2d,
     ecs::{system::Command, world::World},
@@ -36,10 +43,112 @@ use bevy::{
     prelude::{App, AppExtStates, DefaultPlugins, PluginGroup},
     window::{Window, WindowPlugin, WindowResolution},
 };
+use std::path::Path;
 
-fn main() {
+const HEADLESS_ARG: &str = "--headless";
+const REPLAY_ARG: &str = "--replay";
+const LOAD_ARG: &str = "--load";
+const SERVER_ARG: &str = "--server";
+const CONNECT_ARG: &str = "--connect";
+
+fn main() -> Result<()> {
     // TODO: init logger earlier
-    let settings = Settings::load_or_default();
+    let mut settings = Settings::load_or_default();
+    let is_headless = std::env::args().any(|a| a == HEADLESS_ARG);
+    let replay = std::env::args()
+        .skip_while(|a| a != REPLAY_ARG)
+        .nth(1)
+        .map(|path| Replay::load(Path::new(&path)))
+        .transpose()?;
+
+    // a replay is played through the player input, which doesn't exist without a window
+    ensure!(
+        replay.is_none() || !is_headless,
+        "{} can't be used with {}",
+        REPLAY_ARG,
+        HEADLESS_ARG,
+    );
+
+    if let Some(replay) = &replay {
+        // display and audio settings stay as they are, since they don't affect the game
+        settings.game = replay.settings.game.clone();
+        settings.game.seed = replay.seed;
+        settings.controls = replay.settings.controls.clone();
+    }
+
+    let net = if let Some(address) = std::env::args().skip_while(|a| a != CONNECT_ARG).nth(1) {
+        // a client only shows the world of the server, so there's one local player at most
+        settings.game.players = 1;
+        Some(NetPlugin::Client(address))
+    } else if std::env::args().any(|a| a == SERVER_ARG) {
+        if is_headless {
+            settings.game.players = 0; // a dedicated server
+        }
+
+        let port = std::env::args()
+            .skip_while(|a| a != SERVER_ARG)
+            .nth(1)
+            .and_then(|a| a.parse().ok())
+            .unwrap_or(DEFAULT_PORT);
+
+        Some(NetPlugin::Server(port))
+    } else {
+        None
+    };
+
+    let is_client = matches!(net, Some(NetPlugin::Client(_)));
+
+    let load = std::env::args().skip_while(|a| a != LOAD_ARG).nth(1);
+
+    // a replay is recorded from the very start, there's no way to save a game without window, and
+    // the world of a client comes from the server
+    ensure!(
+        load.is_none() || (replay.is_none() && !is_headless && !is_client),
+        "{} can't be used with {}, {} or {}",
+        LOAD_ARG,
+        REPLAY_ARG,
+        HEADLESS_ARG,
+        CONNECT_ARG,
+    );
+
+    let save = load
+        .map(|path| SaveGame::load(Path::new(&path)))
+        .transpose()?;
+
+    if let Some(save) = &save {
+        settings.game.scenario = ScenarioSettings::Waves;
+        settings.game.difficulty = save.difficulty;
+    }
+
+    let scenario = match settings.game.scenario {
+        _ if is_client => Scenario::new(RemoteScenario),
+        ScenarioSettings::BenchProjectiles => Scenario::new(BenchProjectilesScenario::default()),
+        ScenarioSettings::BenchZombies => Scenario::new(BenchZombiesScenario::default()),
+        ScenarioSettings::Test => Scenario::new(TestScenario),
+        ScenarioSettings::TestBotSpread => Scenario::new(TestBotSpreadScenario),
+        ScenarioSettings::Waves => match &save {
+            Some(save) => Scenario::new(WavesScenario::restore(&save.scenario)),
+            _ => Scenario::new(WavesScenario::new(
+                &settings.game.campaign,
+                settings.game.level,
+            )),
+        },
+    };
+
+    if is_headless {
+        let log_plugin = init_log_plugin(&settings);
+        let mut application = headless::build(settings, scenario);
+        application.add_plugins(log_plugin);
+
+        if let Some(net) = net {
+            application.add_plugins(net);
+        }
+
+        application.run();
+        return Ok(());
+    }
+
+    let mut rng = RngResource::new_or_random(settings.game.seed);
     let mut application = App::new();
 
     application.add_plugins(
@@ -61,14 +170,22 @@ fn main() {
             }),
     );
 
-    let scenario = match settings.game.scenario {
-        ScenarioSettings::BenchProjectiles => Scenario::new(BenchProjectilesScenario::default()),
-        ScenarioSettings::BenchZombies => Scenario::new(BenchZombiesScenario::default()),
-        ScenarioSettings::Test => Scenario::new(TestScenario),
-        ScenarioSettings::TestBotSpread => Scenario::new(TestBotSpreadScenario),
-        ScenarioSettings::Waves => Scenario::new(WavesScenario::new(settings.game.level)),
+    let replay_plugin = match replay {
+        Some(replay) => Some(ReplayPlugin::Play(replay)),
+        // a loaded game doesn't start from the seed, and remote players aren't recorded, so such
+        // a record couldn't be played
+        None if save.is_some() || net.is_some() => None,
+        None => Some(ReplayPlugin::Record),
     };
 
+    if let Some(replay_plugin) = replay_plugin {
+        application.add_plugins(replay_plugin);
+    }
+
+    if let Some(net) = net {
+        application.add_plugins(net);
+    }
+
     if settings.game.debug {
         std::env::set_var("RUST_BACKTRACE", "1");
         application.add_plugins(DebugPlugin);
@@ -78,7 +195,7 @@ fn main() {
     application
         .add_plugins(ActorPlugin)
         .add_plugins(AmbienceFxPlugin)
-        .add_plugins(AudioPlugin::new(settings.audio.sources))
+        .add_plugins(AudioPlugin::new(settings.audio.sources, rng.fork()))
         .add_plugins(BloodPlugin)
         .add_plugins(BonusPlugin)
         .add_plugins(BotPlugin)
@@ -94,11 +211,15 @@ fn main() {
         .add_plugins(KineticsPlugin)
         .add_plugins(LoadingPlugin)
         .add_plugins(MeleePlugin)
+        .add_plugins(NavigationPlugin)
         .add_plugins(ParticlePlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(ProjectilePlugin)
+        .add_plugins(SavePlugin(save))
         .add_plugins(ScenarioPlugin)
+        .add_plugins(SimulationPlugin)
         .add_plugins(SkipLoaderPlugin)
+        .add_plugins(SmoothingPlugin)
         .add_plugins(StatusBarPlugin)
         .add_plugins(TerrainPlugin)
         .add_plugins(TileMapPlugin)
@@ -106,10 +227,13 @@ fn main() {
         .add_plugins(WeaponPlugin)
         .init_state::<AppState>()
         .insert_resource(AssetStorage::default())
+        .insert_resource(rng)
         .insert_resource(scenario)
         .insert_resource(settings)
         .add_state_system_enter(AppState::Game, init_game)
         .run();
+
+    return Ok(());
 }
 
 fn init_log_plugin(settings: &Settings) -> LogPlugin {
@@ -129,5 +253,8 @@ fn init_log_plugin(settings: &Settings) -> LogPlugin {
 
 fn init_game(world: &mut World) {
     CursorGrab(true).apply(world);
-    world.spawn(Camera2d).insert(MainCamera);
+    world
+        .spawn(Camera2d)
+        .insert(MainCamera)
+        .insert(Smoothing::default());
 }
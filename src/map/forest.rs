@@ -1,7 +1,9 @@
 use crate::{
     data::{LAYER_GROUND, LAYER_TREE, WORLD_SIZE, WORLD_SIZE_HALF, WORLD_SIZE_VISUAL},
     map::Map,
-    plugin::{AudioPlay, AudioTracker, TerrainSpawn, TileBlend},
+    plugin::{
+        AudioPlay, AudioTracker, Obstacle, ObstacleMaterial, ObstacleSpawn, TerrainSpawn, TileBlend,
+    },
     util::ext::{RngExt2, Vec2Ext},
 };
 use bevy::{
@@ -17,6 +19,8 @@ const TREES_DENSITY: f32 = 0.025;
 const TREE_BUFFER_ZONE: f32 = 3.2;
 const TREE_FIND_POSITION_ATTEMPTS: usize = 32;
 const BLUFF_SPRITE_SIZE: f32 = 4.0;
+/// Trunk radius relative to the tree sprite size
+const TRUNK_RADIUS: f32 = 0.06;
 
 pub struct ForestMap;
 
@@ -105,16 +109,33 @@ fn spawn_trees(world: &mut World) {
                     0.95,
                 );
 
+                let direction = rng.random_range(0.0..TAU);
+                let size = rng.gen_range_safely(*size_min, *size_max);
+
                 TileBlend::Image {
                     image,
                     color: color.into(),
                     position: position.extend(LAYER_TREE),
-                    direction: rng.random_range(0.0..TAU),
-                    size: rng.gen_range_safely(*size_min, *size_max),
+                    direction,
+                    size,
                     flip: rng.random(),
                 }
                 .apply(world);
 
+                // the trees out of the world are only seen, so their trunks aren't needed
+                if position.abs().max_element() < WORLD_SIZE_HALF {
+                    ObstacleSpawn {
+                        obstacle: Obstacle::Circle {
+                            radius: size * TRUNK_RADIUS,
+                        },
+                        material: ObstacleMaterial::Wood,
+                        position,
+                        rotation: direction,
+                        color: None,
+                    }
+                    .apply(world);
+                }
+
                 occupied_positions.push(position);
                 break;
             }
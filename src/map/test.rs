@@ -1,5 +1,13 @@
-use crate::{map::Map, plugin::TerrainSpawn};
-use bevy::ecs::world::World;
+use crate::{
+    map::Map,
+    plugin::{Obstacle, ObstacleMaterial, ObstacleSpawn, TerrainSpawn},
+};
+use bevy::{
+    color::{palettes::css::GRAY, Color},
+    ecs::{system::Command, world::World},
+    math::Vec2,
+};
+use std::f32::consts::FRAC_PI_6;
 
 pub struct TestMap;
 
@@ -8,5 +16,38 @@ impl Map for TestMap {
         world.commands().queue(TerrainSpawn {
             image: "terrain/test.png",
         });
+
+        let color = Some(Color::from(GRAY));
+
+        ObstacleSpawn {
+            obstacle: Obstacle::Box {
+                half_size: Vec2::new(0.2, 3.0),
+            },
+            material: ObstacleMaterial::Stone,
+            position: Vec2::new(6.0, 0.0),
+            rotation: 0.0,
+            color,
+        }
+        .apply(world);
+
+        ObstacleSpawn {
+            obstacle: Obstacle::Box {
+                half_size: Vec2::new(1.0, 1.0),
+            },
+            material: ObstacleMaterial::Stone,
+            position: Vec2::new(-5.0, 5.0),
+            rotation: FRAC_PI_6,
+            color,
+        }
+        .apply(world);
+
+        ObstacleSpawn {
+            obstacle: Obstacle::Circle { radius: 0.8 },
+            material: ObstacleMaterial::Stone,
+            position: Vec2::new(-5.0, -5.0),
+            rotation: 0.0,
+            color,
+        }
+        .apply(world);
     }
 }
@@ -6,6 +6,8 @@ pub enum ActorAction {
     Attack,
     Reload,
     AimToggle,
+    /// Switches the fire mode of the weapon once pressed
+    SelectFireMode,
 }
 
 pub type ActorActions = EnumSet<ActorAction>;
@@ -16,6 +18,7 @@ pub trait ActorActionsExt {
     fn is_sprinting(self) -> bool;
     fn is_attacking(self) -> bool;
     fn is_reloading(self) -> bool;
+    fn is_selecting_fire_mode(self) -> bool;
 }
 
 impl ActorActionsExt for ActorActions {
@@ -38,4 +41,8 @@ impl ActorActionsExt for ActorActions {
     fn is_reloading(self) -> bool {
         return self.contains(ActorAction::Reload);
     }
+
+    fn is_selecting_fire_mode(self) -> bool {
+        return self.contains(ActorAction::SelectFireMode);
+    }
 }
@@ -1,17 +1,18 @@
 use crate::{
     data::{LAYER_ACTOR, TRANSFORM_SCALE},
     plugin::{
-        actor::{action::ActorActions, config::ActorKind, Actor, ActorConfig},
+        actor::{action::ActorActions, Actor, ActorConfig},
         bot::Bot,
         camera_target::CameraTarget,
         collision::Collision,
         kinetics::Kinetics,
         player::Player,
-        Breath, Crosshair, Footsteps, Health, StatusBar,
+        Breath, Crosshair, Footsteps, Health, Smoothing, StatusBar,
     },
     resource::Settings,
 };
 use bevy::{
+    color::Color,
     ecs::{hierarchy::Children, system::Command},
     math::{Quat, Vec2},
     prelude::{AssetServer, Entity, Transform, World},
@@ -48,6 +49,10 @@ impl Command for ActorSet {
                 },
                 Sprite {
                     image,
+                    color: self
+                        .config
+                        .tint
+                        .map_or(Color::WHITE, |[r, g, b]| Color::srgb(r, g, b)),
                     ..Default::default()
                 },
             ))
@@ -55,11 +60,12 @@ impl Command for ActorSet {
                 radius: self.config.radius,
             })
             .insert(Kinetics::new(self.config.mass))
+            .insert(Smoothing::default())
             .insert(Actor::new(self.config, difficulty))
             .insert(Health::new(self.config.health))
             .insert(Footsteps::default());
 
-        if let ActorKind::Human = self.config.kind {
+        if self.config.kind.is_human() {
             entity_mut.insert(Breath::default());
         }
     }
@@ -1,19 +1,42 @@
-use crate::plugin::bot::BotConfig;
-use std::{f32::consts::TAU, time::Duration};
+use crate::{plugin::bot::BotConfig, util::serde_secs};
+use anyhow::{ensure, Result};
+use serde::Deserialize;
+use std::time::Duration;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ActorKind {
     Human,
     Zombie,
+    /// Hostile people, who fight both humans and zombies
+    Bandit,
 }
 
+impl ActorKind {
+    /// Whether it's a person, either friendly or not
+    pub const fn is_human(self) -> bool {
+        return matches!(self, Self::Human | Self::Bandit);
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ActorConfig {
+    /// Taken from the name of the directory the config is located in
+    #[serde(skip)]
+    pub name: String,
+    /// Name of another actor which images and sounds to use instead of own ones
+    #[serde(default)]
+    pub assets: Option<String>,
+    /// Multiplies colors of the images, so actors sharing them look different
+    #[serde(default)]
+    pub tint: Option<[f32; 3]>,
     pub kind: ActorKind,
-    pub name: &'static str,
     // movement
     pub movement_velocity: f32,
     pub rotation_velocity: f32,
     pub sprint_factor: f32,
+    #[serde(with = "serde_secs")]
     pub stamina: Duration,
     // health
     pub health: f32,
@@ -25,74 +48,46 @@ pub struct ActorConfig {
     pub melee_damage: f32,
     pub melee_distance: f32,
     pub melee_distance_angular: f32,
+    #[serde(with = "serde_secs")]
     pub melee_interval: Duration,
     // shooting
     pub recoil_factor: f32,
     // misc
-    pub bot: &'static BotConfig,
-    pub images: &'static [u8],
+    pub bot: BotConfig,
+    pub images: Vec<u8>,
 }
 
 impl ActorConfig {
-    const HUMAN_HEALTH: f32 = 9.0;
-
-    pub const HUMAN: Self = Self {
-        kind: ActorKind::Human,
-        name: "human",
-        movement_velocity: 2.8,
-        rotation_velocity: 3.5,
-        sprint_factor: 1.6,
-        stamina: Duration::from_secs(16),
-        health: Self::HUMAN_HEALTH,
-        pain_threshold: 0.02,
-        radius: 0.25,
-        mass: 85.0,
-        melee_damage: Self::HUMAN_HEALTH / 16.0,
-        melee_distance: 0.7,
-        melee_distance_angular: TAU / 5.0,
-        melee_interval: Duration::from_millis(600),
-        recoil_factor: 1.0,
-        bot: BotConfig::HUMAN,
-        images: &[1, 2],
-    };
-
-    pub const ZOMBIE: Self = Self {
-        kind: ActorKind::Zombie,
-        name: "zombie",
-        movement_velocity: Self::HUMAN.movement_velocity * 0.33,
-        rotation_velocity: Self::HUMAN.rotation_velocity * 0.4,
-        sprint_factor: Self::HUMAN.sprint_factor,
-        stamina: Duration::from_secs(10),
-        health: Self::HUMAN.health / 2.0,
-        pain_threshold: f32::INFINITY, // disabled
-        radius: 0.21,
-        mass: 70.0,
-        melee_damage: Self::HUMAN.health / 10.0,
-        melee_distance: Self::HUMAN.melee_distance,
-        melee_distance_angular: Self::HUMAN.melee_distance_angular,
-        melee_interval: Self::HUMAN.melee_interval,
-        recoil_factor: 6.0,
-        bot: BotConfig::ZOMBIE,
-        images: &[0, 1, 2],
-    };
-
-    pub const ZOMBIE_AGILE: Self = Self {
-        kind: ActorKind::Zombie,
-        name: "zombie_agile",
-        movement_velocity: Self::HUMAN.movement_velocity * 0.8,
-        rotation_velocity: 4.0,
-        stamina: Duration::from_secs(60),
-        health: Self::ZOMBIE.health / 2.0,
-        radius: 0.19,
-        mass: 45.0,
-        melee_damage: Self::ZOMBIE.melee_damage / 2.0,
-        bot: BotConfig::ZOMBIE_AGILE,
-        images: &[0],
-        ..Self::ZOMBIE
-    };
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.movement_velocity > 0.0,
+            "Movement velocity must be greater than zero"
+        );
+        ensure!(
+            self.rotation_velocity > 0.0,
+            "Rotation velocity must be greater than zero"
+        );
+        ensure!(
+            self.sprint_factor >= 1.0,
+            "Sprint factor must be at least 1"
+        );
+        ensure!(self.health > 0.0, "Health must be greater than zero");
+        ensure!(
+            self.pain_threshold >= 0.0,
+            "Pain threshold must be non-negative",
+        );
+        ensure!(self.radius > 0.0, "Radius must be greater than zero");
+        ensure!(self.mass > 0.0, "Mass must be greater than zero");
+        ensure!(
+            !self.images.is_empty(),
+            "At least one image must be specified"
+        );
+        self.bot.validate()?;
+        return Ok(());
+    }
 
     pub fn get_assets_path(&self) -> String {
-        return format!("actors/{}", self.name);
+        return format!("actors/{}", self.assets.as_ref().unwrap_or(&self.name));
     }
 
     pub fn get_image_path(&self, mut suffix: u8) -> String {
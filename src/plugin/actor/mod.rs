@@ -3,10 +3,11 @@ mod command;
 mod component;
 mod config;
 mod event;
+mod registry;
 
-pub use self::{action::*, command::*, component::*, config::*, event::*};
+pub use self::{action::*, command::*, component::*, config::*, event::*, registry::*};
 use crate::{
-    plugin::{debug::debug_line, kinetics::Kinetics, player::PlayerSystems},
+    plugin::{debug::debug_line, kinetics::Kinetics, player::PlayerSystems, Noise},
     state::AppState,
     util::{
         ext::{AppExt, QuatExt, Vec2Ext},
@@ -28,8 +29,14 @@ pub struct ActorPlugin;
 
 impl Plugin for ActorPlugin {
     fn build(&self, app: &mut App) {
+        let registry = ActorRegistry::load().unwrap_or_else(|error| {
+            panic!("Failed to load actors: {:?}", error);
+        });
+
+        app.insert_resource(registry);
         app.add_message::<ActorDeathEvent>();
-        app.add_state_system(AppState::Game, on_update.after(PlayerSystems));
+        app.add_message::<Noise>();
+        app.add_state_system_fixed(AppState::Game, on_update.after(PlayerSystems));
     }
 }
 
@@ -0,0 +1,74 @@
+use crate::{plugin::ActorConfig, util::load_definitions};
+use anyhow::{Context, Result};
+use bevy::ecs::resource::Resource;
+use std::path::Path;
+
+const DIRECTORY: &str = "assets/actors";
+const FILE: &str = "actor.toml";
+
+/// All the actor archetypes loaded from `assets/actors/<name>/actor.toml` files. Configs are
+/// leaked once on start so they can be referenced statically just like the rest of configs
+#[derive(Resource)]
+pub struct ActorRegistry {
+    actors: Vec<&'static ActorConfig>,
+}
+
+impl ActorRegistry {
+    pub fn load() -> Result<Self> {
+        return Self::load_from(Path::new(DIRECTORY));
+    }
+
+    fn load_from(directory: &Path) -> Result<Self> {
+        let mut actors = Vec::new();
+
+        for definition in load_definitions(directory, FILE)? {
+            let mut actor = definition.parse::<ActorConfig>()?;
+            actor.name = definition.name;
+            actor
+                .validate()
+                .with_context(|| format!("Invalid actor {}", definition.path.display()))?;
+            actors.push(&*Box::leak(Box::new(actor)));
+        }
+
+        return Ok(Self { actors });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&'static ActorConfig> {
+        let actor = self.actors.iter().find(|a| a.name == name).copied();
+
+        if actor.is_none() {
+            log::warn!("Actor `{}` not found", name);
+        }
+
+        return actor;
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::plugin::ActorKind;
+
+    #[test]
+    fn load_assets() {
+        let registry = ActorRegistry::load().unwrap();
+
+        let zombie = registry.get("zombie").map(|a| a.kind);
+        assert_eq!(zombie, Some(ActorKind::Zombie));
+
+        // inherited from the zombie
+        let agile = registry
+            .get("zombie_agile")
+            .map(|a| (a.kind, a.bot.is_silly));
+        assert_eq!(agile, Some((ActorKind::Zombie, true)));
+
+        // shares images and sounds with the human
+        let bandit = registry.get("bandit");
+        assert_eq!(bandit.map(|a| a.kind), Some(ActorKind::Bandit));
+        assert_eq!(
+            bandit.map(ActorConfig::get_assets_path).as_deref(),
+            Some("actors/human"),
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::util::{ext::Vec2Ext, SmartString};
-use bevy::{audio::Volume, math::Vec2, prelude::PlaybackSettings};
+use bevy::{audio::Volume, ecs::entity::Entity, math::Vec2, prelude::PlaybackSettings};
 use std::time::Duration;
 
 #[derive(Clone)]
@@ -11,6 +11,8 @@ pub struct AudioPlay {
     pub speed: f32,
     pub source: Option<Vec2>,
     pub duration: Duration,
+    /// The entity which may stop the sound early. It's stopped anyway once the entity is gone
+    pub owner: Option<Entity>,
 }
 
 impl AudioPlay {
@@ -34,6 +36,7 @@ impl AudioPlay {
         speed: 1.0,
         source: None,
         duration: Self::DURATION_ONCE,
+        owner: None,
     };
 
     pub fn calc_spatial_volume(&self, volume: f32, source: Vec2, listener: Vec2) -> f32 {
@@ -3,7 +3,7 @@ use bevy::{
     platform::collections::{HashMap, HashSet},
     prelude::{Assets, AudioSource, Handle, Resource},
 };
-use rand::{seq::SliceRandom, SeedableRng};
+use rand::seq::SliceRandom;
 use rand_pcg::Pcg32;
 use regex::Regex;
 use std::borrow::Cow;
@@ -19,17 +19,15 @@ pub struct AudioStorage {
     generator: Pcg32,
 }
 
-impl Default for AudioStorage {
-    fn default() -> Self {
+impl AudioStorage {
+    pub fn new(generator: Pcg32) -> Self {
         return Self {
             groups: HashMap::new(),
             missing: HashSet::new(),
-            generator: Pcg32::seed_from_u64(193_330),
+            generator,
         };
     }
-}
 
-impl AudioStorage {
     pub fn index(&mut self, assets: &Assets<AudioSource>, asset_server: &AssetServer) {
         log::debug!("Indexing");
 
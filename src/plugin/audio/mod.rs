@@ -2,7 +2,10 @@ mod audio_play;
 mod audio_storage;
 
 pub use self::{audio_play::*, audio_storage::*};
-use crate::plugin::{camera_target::CameraTarget, Heartbeat};
+use crate::{
+    plugin::{camera_target::CameraTarget, Heartbeat},
+    util::SmartString,
+};
 use bevy::{
     app::Update,
     audio::{AudioPlayer, AudioSink, Volume},
@@ -12,23 +15,27 @@ use bevy::{
         Vec2, With,
     },
 };
+use rand_pcg::Pcg32;
 use std::{sync::Mutex, time::Duration};
 
 pub struct AudioPlugin {
     limit: usize,
+    generator: Pcg32,
 }
 
 impl AudioPlugin {
-    pub fn new(limit: usize) -> Self {
-        return Self { limit };
+    /// Sounds are chosen by a separate generator since their count depends on what is heard
+    pub fn new(limit: usize, generator: Pcg32) -> Self {
+        return Self { limit, generator };
     }
 }
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(AudioStorage::default());
+        app.insert_resource(AudioStorage::new(self.generator.clone()));
         app.insert_resource(AudioTracker {
             queue: Mutex::new(Vec::with_capacity(self.limit)),
+            stops: Mutex::new(Vec::new()),
             playing: 0,
             limit: self.limit,
             listener: Vec2::ZERO,
@@ -42,6 +49,7 @@ impl Plugin for AudioPlugin {
 #[derive(Resource)]
 pub struct AudioTracker {
     queue: Mutex<Vec<AudioPlay>>,
+    stops: Mutex<Vec<(Entity, &'static str)>>,
     limit: usize, // TODO: autoupdate from settings
     pub playing: usize,
     pub listener: Vec2,
@@ -91,6 +99,25 @@ impl AudioTracker {
         }
     }
 
+    /// Stops sounds of the path which the entity owns, including the queued ones
+    pub fn stop(&self, owner: Entity, path: &'static str) {
+        let (Ok(mut queue), Ok(mut stops)) = (self.queue.lock(), self.stops.lock()) else {
+            log::error!("Unable to stop audio. Audio tracker is poisoned");
+            return;
+        };
+
+        queue.retain(|a| a.owner != Some(owner) || a.path.as_ref() != path);
+        stops.push((owner, path));
+    }
+
+    fn take_stops(&self) -> Vec<(Entity, &'static str)> {
+        return self
+            .stops
+            .lock()
+            .map(|mut s| std::mem::take(&mut *s))
+            .unwrap_or_default();
+    }
+
     fn take_queue(&self) -> Vec<AudioPlay> {
         if let Ok(mut queue) = self.queue.lock() {
             if queue.is_empty() {
@@ -107,25 +134,45 @@ impl AudioTracker {
 #[derive(Component)]
 struct Expiration(Duration);
 
+#[derive(Component)]
+struct Owned {
+    owner: Entity,
+    path: SmartString<'static>,
+}
+
 fn on_update(
     mut tracker: ResMut<AudioTracker>,
     mut storage: ResMut<AudioStorage>,
     mut commands: Commands,
-    audio: Query<(Entity, &AudioSink, Option<&Expiration>)>,
+    audio: Query<(Entity, &AudioSink, Option<&Expiration>, Option<&Owned>)>,
+    entities: Query<()>,
     listeners: Query<&Transform, With<CameraTarget>>,
     time: Res<Time>,
 ) {
     crate::util::bench::bench!();
     let now = time.elapsed();
 
-    if let Some(listener) = listeners.iter().next() {
-        tracker.listener = listener.translation.truncate();
+    // with several local players, they listen from the middle of the shared view
+    let (sum, count) = listeners.iter().fold((Vec2::ZERO, 0.0), |(sum, count), l| {
+        return (sum + l.translation.truncate(), count + 1.0);
+    });
+
+    if count > 0.0 {
+        tracker.listener = sum / count;
     }
 
     tracker.playing = 0;
+    let stops = tracker.take_stops();
+
+    for (entity, sink, expiration, owned) in audio.iter() {
+        let is_stopped = owned.is_some_and(|o| {
+            return !entities.contains(o.owner)
+                || stops
+                    .iter()
+                    .any(|(owner, path)| *owner == o.owner && *path == o.path.as_ref());
+        });
 
-    for (entity, sink, expiration) in audio.iter() {
-        if sink.empty() || expiration.is_some_and(|e| now > e.0) {
+        if sink.empty() || expiration.is_some_and(|e| now > e.0) || is_stopped {
             sink.stop();
             commands.entity(entity).despawn();
         } else {
@@ -155,6 +202,13 @@ fn on_update(
             entity.insert(Expiration(now + duration));
         }
 
+        if let Some(owner) = audio.owner {
+            entity.insert(Owned {
+                owner,
+                path: audio.path.clone(),
+            });
+        }
+
         tracker.playing += 1;
     }
 }
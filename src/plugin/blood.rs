@@ -1,7 +1,7 @@
 use crate::{
     data::{LAYER_GROUND, PIXELS_PER_METER},
     plugin::TileBlend,
-    resource::AssetStorage,
+    resource::{AssetStorage, RngResource},
     util::math::interpolate_unbounded,
 };
 use bevy::{
@@ -69,9 +69,10 @@ impl Command for BloodSpawn {
     fn apply(self, world: &mut World) {
         crate::util::bench::bench!();
         let mesh = world.resource::<AssetStorage>().dummy_mesh().clone();
+        let seed = world.resource_mut::<RngResource>().random_range(0.0..500.0);
 
         let material = world.resource_mut::<Assets<Blood>>().add(Blood {
-            seed: rand::rng().random_range(0.0..500.0),
+            seed,
             size: self.size * PIXELS_PER_METER,
         });
 
@@ -1,11 +1,14 @@
 use crate::{
     data::{FONT_PATH, LAYER_BONUS, PIXELS_PER_METER, TRANSFORM_SCALE},
     plugin::{
+        bot::Bot,
         camera::MainCamera,
         collision::{Collision, CollisionSystems},
         player::Player,
-        Actor, ActorKind, Weapon, WeaponConfig, WeaponSet,
+        Actor, ActorKind, AmmoReserve, Inventory, Weapon, WeaponConfig, WeaponPickUp,
+        WeaponRegistry,
     },
+    resource::RngResource,
     state::AppState,
     util::{
         ext::{AppExt, QuatExt, Vec2Ext},
@@ -19,7 +22,9 @@ use bevy::{
         component::Component,
         entity::Entity,
         hierarchy::ChildOf,
+        query::Has,
         system::{Command, Res},
+        world::Mut,
     },
     math::Vec3Swizzles,
     prelude::{
@@ -43,7 +48,7 @@ pub struct BonusPlugin;
 
 impl Plugin for BonusPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(AppState::Game, update_pickup.after(CollisionSystems));
+        app.add_state_system_fixed(AppState::Game, update_pickup.after(CollisionSystems));
         app.add_state_system(AppState::Game, update_image);
         app.add_state_system(AppState::Game, update_label);
     }
@@ -52,11 +57,59 @@ impl Plugin for BonusPlugin {
 pub struct BonusSpawn {
     position: Vec2,
     level: u8,
+    weapon: Option<&'static WeaponConfig>,
+    ammo: Option<u8>,
+    dropped_by: Option<Entity>,
+    lifetime: Duration,
 }
 
 impl BonusSpawn {
     pub fn new(position: Vec2, level: u8) -> Self {
-        return Self { position, level };
+        return Self {
+            position,
+            level,
+            weapon: None,
+            ammo: None,
+            dropped_by: None,
+            lifetime: LIFETIME,
+        };
+    }
+
+    /// Spawns the given weapon instead of a random one of the level
+    pub fn with_weapon(position: Vec2, weapon: &'static WeaponConfig, lifetime: Duration) -> Self {
+        return Self {
+            position,
+            level: weapon.level,
+            weapon: Some(weapon),
+            ammo: None,
+            dropped_by: None,
+            lifetime,
+        };
+    }
+
+    /// A weapon dropped by an actor, with the ammo left in it
+    pub fn dropped(position: Vec2, weapon: &'static WeaponConfig, ammo: u8) -> Self {
+        return Self::with_weapon(position, weapon, LIFETIME).with_ammo(Some(ammo));
+    }
+
+    pub fn with_ammo(mut self, ammo: Option<u8>) -> Self {
+        self.ammo = ammo;
+        return self;
+    }
+
+    /// The actor won't pick it up again until it steps off
+    pub fn dropped_by(mut self, entity: Entity) -> Self {
+        self.dropped_by = Some(entity);
+        return self;
+    }
+
+    /// Returns the bonus entity, unless there was no weapon to spawn
+    pub fn spawn(self, world: &mut World) -> Option<Entity> {
+        let weapon = self.weapon.or_else(|| choose_weapon(world, self.level))?;
+        let bonus = spawn_bonus(world, &self, weapon);
+        spawn_image(world, bonus, weapon);
+        spawn_label(world, bonus, weapon);
+        return Some(bonus);
     }
 }
 
@@ -64,18 +117,93 @@ impl Command for BonusSpawn {
     type Out = ();
 
     fn apply(self, world: &mut World) {
-        if let Some(weapon) = choose_weapon(world, self.level) {
-            let bonus = spawn_bonus(world, self.position, weapon);
-            spawn_image(world, bonus, weapon);
-            spawn_label(world, bonus, weapon);
-        }
+        self.spawn(world);
     }
 }
 
 #[derive(Component)]
-struct Bonus {
-    weapon: &'static WeaponConfig,
-    expiration: Duration,
+pub struct Bonus {
+    pub weapon: &'static WeaponConfig,
+    /// Ammo left in a dropped weapon. A new one is fully loaded
+    pub ammo: Option<u8>,
+    /// An actor which has just dropped it and still stands on it
+    pub dropped_by: Option<Entity>,
+    pub expiration: Duration,
+}
+
+impl Bonus {
+    pub fn ammo(&self) -> u8 {
+        return self.ammo.unwrap_or(self.weapon.ammo_capacity);
+    }
+
+    /// Spare ammo which comes along with a new weapon, but not with a dropped one
+    pub fn reserve(&self) -> u16 {
+        if self.ammo.is_some() {
+            return 0;
+        } else {
+            return u16::from(self.weapon.ammo_capacity)
+                .saturating_mul(AmmoReserve::MAGAZINES_WITH_WEAPON);
+        }
+    }
+
+    /// Whether a bot would take it instead of its weapon, if it has any
+    pub fn is_wanted(&self, kind: ActorKind, weapon: Option<&Weapon>) -> bool {
+        let Some(weapon) = weapon else {
+            // zombies can only use weapons they have got as humans
+            return kind.is_human();
+        };
+
+        if std::ptr::eq(weapon.config, self.weapon) {
+            return self.ammo() > weapon.ammo();
+        } else {
+            return self.weapon.level > weapon.config.level;
+        }
+    }
+}
+
+/// Makes an actor pick up the closest bonus it stands on, even if the weapon replaces a carried one
+pub struct BonusPickUp {
+    pub entity: Entity,
+}
+
+impl Command for BonusPickUp {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        let (Some(transform), Some(body)) = (
+            world.get::<Transform>(self.entity),
+            world.get::<Collision>(self.entity),
+        ) else {
+            return;
+        };
+
+        let position = transform.translation.xy();
+        let reach = RADIUS + body.radius;
+
+        let closest = world
+            .query::<(Entity, &Bonus, &Transform)>()
+            .iter(world)
+            .map(|(e, b, t)| (e, b, t.translation.xy().distance_squared(position)))
+            .filter(|(_, _, distance)| *distance < reach * reach)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(bonus_entity, bonus, _)| {
+                let pick_up = WeaponPickUp {
+                    entity: self.entity,
+                    weapon: bonus.weapon,
+                    ammo: bonus.ammo(),
+                    reserve: bonus.reserve(),
+                };
+
+                return (bonus_entity, pick_up);
+            });
+
+        let Some((bonus_entity, pick_up)) = closest else {
+            return;
+        };
+
+        world.despawn(bonus_entity);
+        pick_up.apply(world);
+    }
 }
 
 #[derive(Component)]
@@ -85,14 +213,23 @@ struct BonusImage;
 struct BonusLabel;
 
 fn update_pickup(
-    bonuses: Query<(Entity, &Bonus, &Transform)>,
-    players: Query<(Entity, &Actor, &Transform, &Collision), With<Player>>,
+    mut bonuses: Query<(Entity, &mut Bonus, &Transform)>,
+    actors: Query<(
+        Entity,
+        &Actor,
+        &Transform,
+        &Collision,
+        Option<&Weapon>,
+        Option<&Inventory>,
+        Has<Player>,
+        Has<Bot>,
+    )>,
     mut commands: Commands,
     time: Res<Time>,
 ) {
     let now = time.elapsed();
 
-    for (bonus_entity, bonus, bonus_transform) in bonuses.iter() {
+    for (bonus_entity, mut bonus, bonus_transform) in bonuses.iter_mut() {
         if now > bonus.expiration {
             commands.entity(bonus_entity).despawn();
             continue;
@@ -100,18 +237,42 @@ fn update_pickup(
 
         let bonus_position = bonus_transform.translation.xy();
 
-        for (player_entity, actor, player_transform, player_body) in players.iter() {
-            if actor.config.kind != ActorKind::Human {
+        if let Some(dropper) = bonus.dropped_by {
+            let is_touching = actors.get(dropper).is_ok_and(|(_, _, t, body, ..)| {
+                t.translation
+                    .xy()
+                    .is_close(bonus_position, RADIUS + body.radius)
+            });
+
+            if !is_touching {
+                bonus.dropped_by = None;
+            }
+        }
+
+        for (entity, actor, transform, body, weapon, inventory, is_player, is_bot) in actors.iter()
+        {
+            // players take what fits the inventory, replacing a weapon only on purpose, while bots
+            // take only what they need
+            let can_pick_up = if is_player {
+                actor.config.kind == ActorKind::Human
+                    && inventory.is_none_or(|i| i.fits(weapon, bonus.weapon))
+            } else {
+                is_bot && bonus.is_wanted(actor.config.kind, weapon)
+            };
+
+            if !can_pick_up || bonus.dropped_by == Some(entity) {
                 continue;
             }
 
-            let player_position = player_transform.translation.xy();
+            let position = transform.translation.xy();
 
-            if player_position.is_close(bonus_position, RADIUS + player_body.radius) {
+            if position.is_close(bonus_position, RADIUS + body.radius) {
                 commands.entity(bonus_entity).despawn();
-                commands.queue(WeaponSet {
-                    entity: player_entity,
-                    weapon: Some(bonus.weapon),
+                commands.queue(WeaponPickUp {
+                    entity,
+                    weapon: bonus.weapon,
+                    ammo: bonus.ammo(),
+                    reserve: bonus.reserve(),
                 });
 
                 break;
@@ -164,7 +325,7 @@ fn choose_weapon(world: &mut World, level: u8) -> Option<&'static WeaponConfig>
         .query_filtered::<Option<&Weapon>, With<Player>>()
         .iter(world)
     {
-        let weapon_name = weapon.map(|w| w.config.name).unwrap_or("");
+        let weapon_name = weapon.map(|w| w.config.name.as_str()).unwrap_or("");
 
         match weapon_of_all_the_players.map(|w| weapon_name == w) {
             None => {
@@ -182,26 +343,34 @@ fn choose_weapon(world: &mut World, level: u8) -> Option<&'static WeaponConfig>
         }
     }
 
-    return WeaponConfig::ALL
-        .choose_weighted(&mut rand::rng(), |w| {
-            if w.level > level || Some(w.name) == weapon_of_all_the_players {
-                return 0.0;
-            } else {
-                return (w.level as f32 / level as f32).powi(2); // more chance for newer weapons
-            }
-        })
-        .ok();
+    return world.resource_scope(|world, mut rng: Mut<RngResource>| {
+        return world
+            .resource::<WeaponRegistry>()
+            .all()
+            .choose_weighted(&mut *rng, |w| {
+                if w.level > level || Some(w.name.as_str()) == weapon_of_all_the_players {
+                    return 0.0;
+                } else {
+                    return (w.level as f32 / level as f32).powi(2); // more chance for newer weapons
+                }
+            })
+            .ok()
+            .copied();
+    });
 }
 
-fn spawn_bonus(world: &mut World, position: Vec2, weapon: &'static WeaponConfig) -> Entity {
+fn spawn_bonus(world: &mut World, spawn: &BonusSpawn, weapon: &'static WeaponConfig) -> Entity {
     let time = world.resource::<Time>().elapsed();
+    let position = spawn.position;
 
     return world
         .spawn((
             Transform::from_xyz(position.x, position.y, LAYER_BONUS).with_scale(TRANSFORM_SCALE),
             Bonus {
                 weapon,
-                expiration: time + LIFETIME,
+                ammo: spawn.ammo,
+                dropped_by: spawn.dropped_by,
+                expiration: time + spawn.lifetime,
             },
         ))
         .id();
@@ -231,7 +400,7 @@ fn spawn_label(world: &mut World, bonus: Entity, weapon: &WeaponConfig) {
     world
         .spawn((
             Transform::from_scale(Vec3::new(0.0, 0.0, 1.5)),
-            Text2d(weapon.name.to_string()),
+            Text2d(weapon.name.clone()),
             TextFont {
                 font: font.into(),
                 font_size: FontSize::Px(PIXELS_PER_METER),
@@ -0,0 +1,187 @@
+use crate::plugin::bot::{BotOrder, BotWeights};
+
+/// Scores of behaviours at their full weight. They set which behaviour is preferred by default,
+/// while the weights of a bot shift it
+const SCORE_IDLE: f32 = 0.01;
+const SCORE_SEARCH: f32 = 0.3;
+const SCORE_ORDER: f32 = 0.4;
+const SCORE_PICKUP: f32 = 0.45;
+const SCORE_ATTACK: f32 = 0.5;
+const SCORE_REGROUP: f32 = 1.0;
+/// Each teammate around takes that much off the wish to retreat
+const RETREAT_TEAMMATE_COURAGE: f32 = 0.1;
+
+/// What a bot may do. Each behaviour scores itself from the situation, and the bot does the one
+/// which scores the most
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behaviour {
+    Idle,
+    Search,
+    Follow,
+    Hold,
+    Advance,
+    Pickup,
+    Attack,
+    Dodge,
+    Retreat,
+    Regroup,
+}
+
+/// What a bot knows about itself and its surroundings when it makes a decision
+pub struct Situation {
+    /// In range of `0.0` and `1.0`
+    pub health: f32,
+    /// Loaded ammo in range of `0.0` and `1.0`. None if the bot is unarmed
+    pub ammo: Option<f32>,
+    pub teammates: usize,
+    /// How close the enemy in sight is, from `0.0` at the max shoot distance to `1.0` right next
+    /// to the bot
+    pub enemy_proximity: Option<f32>,
+    /// How straight the enemy faces the bot, from `0.0` to `1.0`
+    pub enemy_threat: f32,
+    pub has_last_known: bool,
+    pub has_pickup: bool,
+    pub has_leader: bool,
+    pub order: Option<BotOrder>,
+}
+
+impl Behaviour {
+    const ALL: [Self; 10] = [
+        Self::Idle,
+        Self::Search,
+        Self::Follow,
+        Self::Hold,
+        Self::Advance,
+        Self::Pickup,
+        Self::Attack,
+        Self::Dodge,
+        Self::Retreat,
+        Self::Regroup,
+    ];
+
+    /// The highest scoring behaviour. Of equal ones, the earlier is chosen
+    pub fn choose(situation: &Situation, weights: &BotWeights) -> Self {
+        let mut chosen = Self::Idle;
+        let mut chosen_score = 0.0;
+
+        for behaviour in Self::ALL {
+            let score = behaviour.score(situation, weights);
+
+            if score > chosen_score {
+                chosen = behaviour;
+                chosen_score = score;
+            }
+        }
+
+        return chosen;
+    }
+
+    pub fn score(self, s: &Situation, weights: &BotWeights) -> f32 {
+        let has_enemy = s.enemy_proximity.is_some();
+        let order = s.order.filter(|_| !has_enemy);
+
+        return match self {
+            Self::Idle => SCORE_IDLE,
+            Self::Search if s.has_last_known && !has_enemy => SCORE_SEARCH * weights.search,
+            Self::Follow if s.has_leader && order == Some(BotOrder::Follow) => {
+                SCORE_ORDER * weights.obey
+            }
+            Self::Hold if matches!(order, Some(BotOrder::Hold(_))) => SCORE_ORDER * weights.obey,
+            Self::Advance if matches!(order, Some(BotOrder::Attack(_))) => {
+                SCORE_ORDER * weights.obey
+            }
+            Self::Pickup if s.has_pickup && !has_enemy => SCORE_PICKUP * weights.collect,
+            Self::Attack if has_enemy => SCORE_ATTACK * weights.attack,
+            Self::Dodge if has_enemy => s.enemy_threat * weights.dodge,
+            Self::Retreat => match (s.enemy_proximity, s.ammo) {
+                (Some(proximity), Some(ammo)) => {
+                    let courage = 1.0 + s.teammates as f32 * RETREAT_TEAMMATE_COURAGE;
+                    (1.0 - s.health) * proximity * (2.0 - ammo) / courage * weights.retreat
+                }
+                _ => 0.0,
+            },
+            Self::Regroup if s.has_leader && s.order == Some(BotOrder::Regroup) => {
+                SCORE_REGROUP * weights.obey
+            }
+            _ => 0.0,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HUMAN: BotWeights = BotWeights {
+        attack: 1.0,
+        dodge: 0.0,
+        retreat: 0.8,
+        search: 1.0,
+        collect: 1.0,
+        obey: 1.0,
+    };
+
+    const ZOMBIE: BotWeights = BotWeights {
+        retreat: 0.0,
+        obey: 0.0,
+        ..HUMAN
+    };
+
+    fn situation(health: f32, ammo: f32, enemy_proximity: Option<f32>) -> Situation {
+        return Situation {
+            health,
+            ammo: Some(ammo),
+            teammates: 0,
+            enemy_proximity,
+            enemy_threat: 0.0,
+            has_last_known: enemy_proximity.is_some(),
+            has_pickup: false,
+            has_leader: true,
+            order: Some(BotOrder::Follow),
+        };
+    }
+
+    #[test]
+    fn choose() {
+        let calm = situation(1.0, 1.0, None);
+        let fight = situation(1.0, 1.0, Some(0.5));
+        let wounded = situation(0.2, 0.3, Some(0.7));
+        let wounded_far = situation(0.2, 0.3, Some(0.1));
+
+        assert_eq!(Behaviour::choose(&calm, &HUMAN), Behaviour::Follow);
+        assert_eq!(Behaviour::choose(&fight, &HUMAN), Behaviour::Attack);
+        assert_eq!(Behaviour::choose(&wounded, &HUMAN), Behaviour::Retreat);
+        assert_eq!(Behaviour::choose(&wounded_far, &HUMAN), Behaviour::Attack);
+        assert_eq!(Behaviour::choose(&wounded, &ZOMBIE), Behaviour::Attack);
+        assert_eq!(Behaviour::choose(&calm, &ZOMBIE), Behaviour::Idle);
+    }
+
+    #[test]
+    fn pickup_between_fights() {
+        let calm = Situation {
+            has_pickup: true,
+            ..situation(1.0, 1.0, None)
+        };
+
+        let fight = Situation {
+            has_pickup: true,
+            ..situation(1.0, 1.0, Some(0.5))
+        };
+
+        assert_eq!(Behaviour::choose(&calm, &HUMAN), Behaviour::Pickup);
+        assert_eq!(Behaviour::choose(&fight, &HUMAN), Behaviour::Attack);
+    }
+
+    #[test]
+    fn teammates_give_courage() {
+        let alone = situation(0.4, 0.5, Some(0.8));
+
+        let supported = Situation {
+            teammates: 8,
+            ..situation(0.4, 0.5, Some(0.8))
+        };
+
+        assert_eq!(Behaviour::choose(&alone, &HUMAN), Behaviour::Retreat);
+        assert_eq!(Behaviour::choose(&supported, &HUMAN), Behaviour::Attack);
+    }
+}
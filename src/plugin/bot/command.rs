@@ -1,5 +1,8 @@
 use super::Bot;
-use crate::plugin::{actor::Actor, bot::voice::BotVoice, ActorKind};
+use crate::{
+    plugin::{actor::Actor, bot::voice::BotVoice, ActorKind},
+    resource::RngResource,
+};
 use bevy::{
     ecs::system::Command,
     prelude::{Entity, World},
@@ -13,18 +16,16 @@ impl Command for ActorBotSet {
     type Out = ();
 
     fn apply(self, world: &mut World) {
-        let entity_id = self.entity.index_u32();
-
         if let Some(actor) = world.get::<Actor>(self.entity) {
-            let kind = actor.config.kind;
-            let config = actor.config.bot;
+            let config = actor.config;
             let skill = actor.skill;
+            let rng = world.resource_mut::<RngResource>().fork();
 
             let mut entity = world.entity_mut(self.entity);
 
-            entity.insert(Bot::new(config, skill, entity_id));
+            entity.insert(Bot::new(&config.bot, skill, rng));
 
-            if kind != ActorKind::Human {
+            if config.kind == ActorKind::Zombie {
                 entity.insert(BotVoice::default());
             }
         } else {
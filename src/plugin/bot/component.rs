@@ -1,41 +1,65 @@
 use super::sys_detour::Detour;
 use crate::{
-    plugin::bot::BotConfig,
+    plugin::{
+        bot::{BotConfig, BotOrder},
+        navigation::Path,
+        FireMode, WeaponConfig,
+    },
     util::{ext::Fuzz, Timer},
 };
-use bevy::{ecs::component::Component, prelude::Entity};
-use rand::{RngExt, SeedableRng};
+use bevy::{ecs::component::Component, math::Vec2, prelude::Entity};
+use rand::RngExt;
 use rand_pcg::Pcg32;
 use std::{f32::consts::TAU, time::Duration};
 
 #[derive(Component)]
 pub struct Bot {
     pub config: BotConfig,
+    /// An enemy in sight
     pub enemy: Option<Entity>,
+    /// Where an enemy was seen or heard the last time, and when. The bot goes there once it loses
+    /// the enemy
+    pub last_known: Option<(Vec2, Duration)>,
+    /// A bonus in sight which the bot wants to pick up
+    pub pickup: Option<Entity>,
     pub teammates: Vec<Entity>,
+    /// The player whose squad orders the bot carries out
+    pub leader: Option<Entity>,
+    pub order: Option<BotOrder>,
     pub detour: Option<Detour>,
+    /// A way around obstacles to the enemy, when it's out of sight
+    pub path: Option<Path>,
+    pub path_timer: Timer,
     pub update_timer: Timer,
     pub idle_direction: f32,
     pub idle_movement: bool,
     pub was_burst_fire: bool,
-    pub rng: Pcg32, // TODO: use global
+    /// The fire mode chosen for the current shooting, by the distance to the target
+    pub fire_mode: FireMode,
+    /// Forked from the global generator
+    pub rng: Pcg32,
     shooting_state: BotShootingState,
     shooting_timer: Timer,
 }
 
 impl Bot {
-    pub fn new(config: &BotConfig, skill: f32, seed: u32) -> Self {
-        let mut rng = Pcg32::seed_from_u64(seed as u64);
-
+    pub fn new(config: &BotConfig, skill: f32, mut rng: Pcg32) -> Self {
         return Self {
             config: config.clone_with(skill, &mut rng),
             enemy: None,
+            last_known: None,
+            pickup: None,
             teammates: Vec::new(),
+            leader: None,
+            order: None,
             detour: None,
+            path: None,
+            path_timer: Timer::default(),
             update_timer: Timer::default(),
             idle_direction: rng.random_range(0.0..TAU),
             idle_movement: false,
             was_burst_fire: false,
+            fire_mode: FireMode::Semi,
             shooting_state: BotShootingState::Prepare,
             shooting_timer: Timer::default(),
             rng,
@@ -51,7 +75,8 @@ impl Bot {
 
     pub fn get_shooting_state(
         &mut self,
-        is_weapon_automatic: bool,
+        weapon: &WeaponConfig,
+        distance: f32,
         time: Duration,
     ) -> BotShootingState {
         if self.shooting_timer.is_ready(time) {
@@ -67,22 +92,38 @@ impl Bot {
                 }
             };
 
-            self.set_shooting_state(next_state, is_weapon_automatic, time);
+            if next_state == BotShootingState::Shoot {
+                self.fire_mode = self.choose_fire_mode(weapon, distance);
+            }
+
+            self.set_shooting_state(next_state, time);
         }
 
         return self.shooting_state;
     }
 
-    pub fn set_shooting_state(
-        &mut self,
-        state: BotShootingState,
-        is_weapon_automatic: bool,
-        time: Duration,
-    ) {
+    /// Full-auto up close, bursts further and single shots far away, as far as the weapon allows
+    fn choose_fire_mode(&self, weapon: &WeaponConfig, distance: f32) -> FireMode {
+        let reach = distance / self.config.shoot_distance_max;
+
+        let preferred = if reach < BotConfig::FIRE_AUTO_DISTANCE {
+            FireMode::Auto
+        } else if reach < BotConfig::FIRE_BURST_DISTANCE {
+            FireMode::Burst
+        } else {
+            FireMode::Semi
+        };
+
+        return preferred
+            .closest_of(&weapon.fire_modes)
+            .unwrap_or(preferred);
+    }
+
+    pub fn set_shooting_state(&mut self, state: BotShootingState, time: Duration) {
         let duration = match state {
             BotShootingState::Prepare => self.config.shoot_prepare_duration,
             BotShootingState::Shoot => {
-                if is_weapon_automatic {
+                if self.fire_mode != FireMode::Semi {
                     self.config.shoot_burst_duration
                 } else {
                     Duration::ZERO // longer time can result ActorAction::Attack changing multiple times
@@ -101,7 +142,7 @@ impl Bot {
         match (was_target, has_target) {
             // target appeared
             (false, true) => {
-                self.set_shooting_state(BotShootingState::Prepare, false, time);
+                self.set_shooting_state(BotShootingState::Prepare, time);
             }
             // target disappeared
             (true, false) => {
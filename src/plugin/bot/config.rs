@@ -1,10 +1,24 @@
-use crate::util::ext::Fuzz;
+use crate::util::{
+    ext::{Fuzz, Vec2Ext},
+    math::angle_difference,
+    serde_secs,
+};
+use anyhow::{ensure, Result};
+use bevy::math::Vec2;
 use rand::Rng;
-use std::{f32::consts::FRAC_PI_4, time::Duration};
+use serde::Deserialize;
+use std::{
+    f32::consts::{FRAC_PI_4, TAU},
+    time::Duration,
+};
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct BotConfig {
     pub is_silly: bool,
-    pub is_agile: bool,
+    /// Hides behind obstacles from the enemy while reloading
+    pub takes_cover: bool,
+    #[serde(with = "serde_secs")]
     pub reaction: Duration,
     pub spread: f32,
     pub spread_force: f32,
@@ -12,9 +26,38 @@ pub struct BotConfig {
     pub shoot_distance_min: f32,
     pub shoot_distance_max: f32,
     pub angular_deviation: f32,
+    #[serde(with = "serde_secs")]
     pub shoot_prepare_duration: Duration,
+    #[serde(with = "serde_secs")]
     pub shoot_burst_duration: Duration,
+    #[serde(with = "serde_secs")]
     pub shoot_interval: Duration,
+    /// How far the bot sees in front of it
+    pub vision_distance: f32,
+    /// Full width of the vision cone
+    pub vision_angle: f32,
+    /// Enemies closer than that are noticed from any side
+    pub awareness_distance: f32,
+    /// How long the bot remembers where it has seen or heard an enemy the last time
+    #[serde(with = "serde_secs")]
+    pub memory: Duration,
+    pub weights: BotWeights,
+}
+
+/// How much the bot inclines to each behaviour, see `Behaviour::score`. Zero disables a behaviour
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotWeights {
+    pub attack: f32,
+    /// Stepping aside of where the enemy looks
+    pub dodge: f32,
+    /// Backing off the enemy when wounded or short of ammo
+    pub retreat: f32,
+    pub search: f32,
+    /// Picking up weapons lying around
+    pub collect: f32,
+    /// Carrying out squad orders
+    pub obey: f32,
 }
 
 impl BotConfig {
@@ -22,49 +65,72 @@ impl BotConfig {
     pub const IDLE_MOVEMENT_CHANCE: f64 = 0.1;
     pub const REPEAT_SHOOT_CHANCE: f64 = 0.6;
     pub const DODGE_ANGLE: f32 = FRAC_PI_4;
+    pub const PATH_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+    /// How far the enemy may go from the path destination till a new path is needed
+    pub const PATH_DESTINATION_SHIFT: f32 = 2.0;
+    /// How close the bot comes to the last known position of an enemy before it gives up the search
+    pub const SEARCH_REACH: f32 = 1.0;
+    /// How far an ally has to be from the line of fire for the bot to shoot
+    pub const LINE_OF_FIRE_CLEARANCE: f32 = 0.5;
+    /// How far the bot looks for a cover
+    pub const COVER_DISTANCE: f32 = 8.0;
+    /// How far from an obstacle edge the bot hides
+    pub const COVER_GAP: f32 = 0.4;
+    /// How far the bot lets its leader go before following it
+    pub const FOLLOW_DISTANCE: f32 = 4.0;
+    /// How close to its leader the bot has to get to regroup
+    pub const REGROUP_REACH: f32 = 2.0;
+    /// Within this share of the max shoot distance the bot prefers full-auto fire
+    pub const FIRE_AUTO_DISTANCE: f32 = 0.35;
+    /// Within this share of the max shoot distance the bot prefers bursts, and single shots further
+    pub const FIRE_BURST_DISTANCE: f32 = 0.7;
 
-    pub const HUMAN: &'static Self = &Self {
-        is_silly: false,
-        is_agile: false,
-        reaction: Duration::from_millis(250),
-        spread: 0.8,
-        spread_force: 0.2,
-        sprint_distance: 12.0,
-        shoot_distance_min: 6.0,
-        shoot_distance_max: 20.0,
-        angular_deviation: 0.05,
-        shoot_prepare_duration: Duration::from_millis(800),
-        shoot_burst_duration: Duration::from_millis(400),
-        shoot_interval: Duration::from_millis(300),
-    };
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.spread >= 0.0, "Bot spread must be non-negative");
+        ensure!(
+            (0.0..=1.0).contains(&self.spread_force),
+            "Bot spread force must be in range from 0 to 1",
+        );
+        ensure!(
+            self.shoot_distance_min <= self.shoot_distance_max,
+            "Bot min shoot distance must not exceed the max one",
+        );
+        ensure!(
+            self.angular_deviation >= 0.0,
+            "Bot angular deviation must be non-negative",
+        );
+        ensure!(
+            self.vision_distance >= self.awareness_distance,
+            "Bot vision distance must not be less than the awareness one",
+        );
+        ensure!(
+            (0.0..=TAU).contains(&self.vision_angle),
+            "Bot vision angle must be in range from 0 to TAU",
+        );
 
-    pub const ZOMBIE: &'static Self = &Self {
-        is_silly: true,
-        is_agile: false,
-        reaction: Duration::from_millis(500),
-        spread: 1.0,
-        spread_force: 0.4,
-        sprint_distance: Self::HUMAN.sprint_distance,
-        shoot_distance_min: 3.0,
-        shoot_distance_max: 10.0,
-        angular_deviation: 0.16,
-        shoot_prepare_duration: Duration::from_millis(1500),
-        shoot_burst_duration: Duration::from_millis(400),
-        shoot_interval: Duration::from_millis(800),
-    };
+        let weights = &self.weights;
 
-    pub const ZOMBIE_AGILE: &'static Self = &Self {
-        is_silly: true,
-        is_agile: true,
-        reaction: Self::HUMAN.reaction,
-        spread: 0.8,
-        ..*Self::ZOMBIE
-    };
+        ensure!(
+            [
+                weights.attack,
+                weights.dodge,
+                weights.retreat,
+                weights.search,
+                weights.collect,
+                weights.obey,
+            ]
+            .iter()
+            .all(|w| *w >= 0.0),
+            "Bot weights must be non-negative",
+        );
+
+        return Ok(());
+    }
 
     pub fn clone_with<R: Rng>(&self, skill: f32, r: &mut R) -> Self {
         return Self {
             is_silly: self.is_silly,
-            is_agile: self.is_agile,
+            takes_cover: self.takes_cover,
             reaction: self.reaction.fuzz(r).div_f32(skill),
             spread: self.spread.fuzz(r),
             spread_force: f32::min(self.spread_force.fuzz(r), 1.0),
@@ -75,6 +141,70 @@ impl BotConfig {
             shoot_prepare_duration: self.shoot_prepare_duration.div_f32(skill),
             shoot_burst_duration: self.shoot_burst_duration,
             shoot_interval: self.shoot_interval.div_f32(skill),
+            vision_distance: self.vision_distance.fuzz(r),
+            vision_angle: self.vision_angle,
+            awareness_distance: self.awareness_distance,
+            memory: self.memory,
+            weights: self.weights.clone(),
+        };
+    }
+
+    /// Whether the bot, looking in the given direction, may notice something at the given offset
+    /// from it. Obstacles aren't taken into account
+    pub fn can_notice(&self, offset: Vec2, direction: f32) -> bool {
+        if offset.is_short(self.awareness_distance) {
+            return true;
+        }
+
+        if offset.is_long(self.vision_distance) {
+            return false;
+        }
+
+        let angle = angle_difference(direction, Vec2::ZERO.direction_to(offset));
+        return angle.abs() <= self.vision_angle / 2.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn can_notice() {
+        let config = BotConfig {
+            is_silly: false,
+            takes_cover: false,
+            reaction: Duration::ZERO,
+            spread: 0.0,
+            spread_force: 0.0,
+            sprint_distance: 0.0,
+            shoot_distance_min: 0.0,
+            shoot_distance_max: 0.0,
+            angular_deviation: 0.0,
+            shoot_prepare_duration: Duration::ZERO,
+            shoot_burst_duration: Duration::ZERO,
+            shoot_interval: Duration::ZERO,
+            vision_distance: 10.0,
+            vision_angle: FRAC_PI_2,
+            awareness_distance: 2.0,
+            memory: Duration::ZERO,
+            weights: BotWeights {
+                attack: 1.0,
+                dodge: 0.0,
+                retreat: 0.0,
+                search: 1.0,
+                collect: 1.0,
+                obey: 1.0,
+            },
         };
+
+        assert!(config.can_notice(Vec2::new(8.0, 1.0), 0.0));
+        assert!(!config.can_notice(Vec2::new(12.0, 0.0), 0.0));
+        assert!(!config.can_notice(Vec2::new(5.0, 6.0), 0.0));
+        assert!(config.can_notice(Vec2::new(5.0, 6.0), FRAC_PI_2));
+        assert!(!config.can_notice(Vec2::new(-5.0, 0.0), 0.0));
+        assert!(config.can_notice(Vec2::new(-1.5, 0.0), 0.0));
+        assert!(config.can_notice(Vec2::new(-5.0, 0.1), PI));
     }
 }
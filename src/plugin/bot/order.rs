@@ -0,0 +1,27 @@
+use bevy::math::Vec2;
+
+/// An order a player gives to allied bots around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SquadOrder {
+    Follow,
+    Hold,
+    Attack,
+    Regroup,
+}
+
+impl SquadOrder {
+    pub const ALL: [Self; 4] = [Self::Follow, Self::Hold, Self::Attack, Self::Regroup];
+}
+
+/// A squad order as a bot carries it out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BotOrder {
+    /// Stays near the leader, fighting enemies on the way
+    Follow,
+    /// Stays at the position and shoots from there, without chasing anyone
+    Hold(Vec2),
+    /// Goes to the position and follows the leader again once it's there
+    Attack(Vec2),
+    /// Runs back to the leader ignoring enemies, then follows it
+    Regroup,
+}
@@ -1,10 +1,18 @@
 use crate::{
-    plugin::{bot::Bot, Actor},
-    util::ext::Fuzz,
+    plugin::{
+        bot::Bot,
+        debug::{debug_circle, debug_line},
+        Actor, Bonus, Obstacle, Weapon,
+    },
+    util::{
+        ext::{Fuzz, QuatExt, Vec2Ext},
+        Transform2D,
+    },
 };
 use bevy::{
+    color::{palettes::css::BLUE, Alpha},
     ecs::batching::BatchingStrategy,
-    math::Vec3Swizzles,
+    math::{Vec2, Vec3Swizzles},
     prelude::{Entity, Query, Res, Transform},
     time::Time,
 };
@@ -12,17 +20,20 @@ use std::time::Duration;
 
 const TEAMMATES_MAX: usize = 8;
 const UPDATE_INTERVAL: Duration = Duration::from_millis(1500);
+const DEBUG_VISION: bool = false;
 
 pub fn on_update(
-    mut bots: Query<(&mut Bot, Entity, &Actor, &Transform)>,
+    mut bots: Query<(&mut Bot, Entity, &Actor, &Transform, Option<&Weapon>)>,
     actors: Query<(Entity, &Actor, &Transform)>,
+    bonuses: Query<(Entity, &Bonus, &Transform)>,
+    obstacles: Query<(&Transform, &Obstacle)>,
     time: Res<Time>,
 ) {
     let time = time.elapsed();
 
     bots.par_iter_mut()
         .batching_strategy(BatchingStrategy::fixed(32))
-        .for_each(|(mut bot, e1, a1, t1)| {
+        .for_each(|(mut bot, e1, a1, t1, weapon)| {
             if bot.update_timer.is_enabled() && !bot.update_timer.is_ready(time) {
                 return;
             }
@@ -33,6 +44,11 @@ pub fn on_update(
             bot.enemy = None;
 
             let p1 = t1.translation.xy();
+            let direction = t1.rotation.angle_z();
+
+            if DEBUG_VISION {
+                draw_vision(&bot, p1, direction);
+            }
 
             let mut enemy_distance = f32::MAX;
             let mut teammates = Teammates::new();
@@ -42,20 +58,68 @@ pub fn on_update(
                     continue;
                 }
 
-                let distance = p1.distance_squared(t2.translation.truncate());
+                let p2 = t2.translation.xy();
+                let distance = p1.distance_squared(p2);
 
                 if a1.config.kind == a2.config.kind {
                     teammates.try_add(e2, distance);
-                } else if distance < enemy_distance {
+                } else if distance < enemy_distance
+                    && bot.config.can_notice(p2 - p1, direction)
+                    && is_in_sight(p1, p2, &obstacles)
+                {
                     bot.enemy = Some(e2);
+                    bot.last_known = Some((p2, time));
                     enemy_distance = distance;
                 }
             }
 
+            let memory = bot.config.memory;
+
+            if bot
+                .last_known
+                .is_some_and(|(_, known)| time > known + memory)
+            {
+                bot.last_known = None;
+            }
+
             bot.teammates = teammates.teammates;
+            bot.pickup = None;
+            let mut pickup_distance = f32::MAX;
+
+            for (entity, bonus, transform) in bonuses.iter() {
+                let position = transform.translation.xy();
+                let distance = p1.distance_squared(position);
+
+                if distance < pickup_distance
+                    && bonus.is_wanted(a1.config.kind, weapon)
+                    && bot.config.can_notice(position - p1, direction)
+                    && is_in_sight(p1, position, &obstacles)
+                {
+                    bot.pickup = Some(entity);
+                    pickup_distance = distance;
+                }
+            }
         });
 }
 
+fn is_in_sight(from: Vec2, to: Vec2, obstacles: &Query<(&Transform, &Obstacle)>) -> bool {
+    return !obstacles
+        .iter()
+        .any(|(t, o)| o.blocks(Transform2D::from(t), from, to));
+}
+
+fn draw_vision(bot: &Bot, position: Vec2, direction: f32) {
+    let color = BLUE.with_alpha(0.3);
+    let half_angle = bot.config.vision_angle / 2.0;
+
+    for angle in [direction - half_angle, direction + half_angle] {
+        let edge = position + Vec2::from_length(bot.config.vision_distance, angle);
+        debug_line(position, edge, color);
+    }
+
+    debug_circle(position, bot.config.awareness_distance, color);
+}
+
 struct Teammates {
     teammates: Vec<Entity>,
     distances: Vec<f32>,
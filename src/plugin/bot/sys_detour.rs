@@ -1,12 +1,13 @@
 use crate::{
     plugin::{bot::Bot, Actor},
+    resource::RngResource,
     util::{ext::Vec2Ext, math::angle_difference, Timer},
 };
 use bevy::{
     ecs::{
         entity::Entity,
         schedule::ScheduleConfigs,
-        system::{Local, Res, ScheduleSystem},
+        system::{Local, Res, ResMut, ScheduleSystem},
     },
     math::Vec2,
     platform::collections::HashMap,
@@ -58,6 +59,7 @@ pub fn on_update() -> ScheduleConfigs<ScheduleSystem> {
 fn on_update_inner(
     mut bots: Query<(Entity, &mut Bot, &Transform)>,
     actors: Query<&Transform, With<Actor>>,
+    mut rng: ResMut<RngResource>,
 ) {
     crate::util::bench::bench!();
     let mut directions_by_target = HashMap::new();
@@ -92,7 +94,7 @@ fn on_update_inner(
 
                 bot.1.detour = Some(Detour {
                     angle: direction.angle,
-                    distance: rand::rng().random_range(DISTANCE_MIN..DISTANCE_MAX),
+                    distance: rng.random_range(DISTANCE_MIN..DISTANCE_MAX),
                 });
             }
         }
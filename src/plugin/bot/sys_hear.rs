@@ -0,0 +1,73 @@
+use crate::plugin::{
+    bot::Bot,
+    debug::{debug_circle, debug_line},
+    Actor, Noise,
+};
+use bevy::{
+    color::{palettes::css::PURPLE, Alpha},
+    ecs::message::MessageReader,
+    math::{Vec2, Vec3Swizzles},
+    prelude::{Query, Res, Transform},
+    time::Time,
+};
+
+const DEBUG: bool = false;
+
+/// A bot which doesn't see any enemy goes to check the loudest noise of enemies it hears
+pub fn on_update(
+    mut noises: MessageReader<Noise>,
+    mut bots: Query<(&mut Bot, &Actor, &Transform)>,
+    actors: Query<&Actor>,
+    time: Res<Time>,
+) {
+    crate::util::bench::bench!();
+
+    let noises = noises.read().copied().collect::<Vec<_>>();
+
+    if noises.is_empty() {
+        return;
+    }
+
+    let time = time.elapsed();
+
+    if DEBUG {
+        for noise in &noises {
+            debug_circle(noise.position, noise.loudness, PURPLE.with_alpha(0.2));
+        }
+    }
+
+    for (mut bot, actor, transform) in bots.iter_mut() {
+        if bot.enemy.is_some() {
+            continue;
+        }
+
+        let position = transform.translation.xy();
+        let mut heard: Option<(Vec2, f32)> = None;
+
+        for noise in &noises {
+            let is_enemy = noise
+                .source
+                .and_then(|s| actors.get(s).ok())
+                .is_none_or(|a| a.config.kind != actor.config.kind);
+
+            if !is_enemy || !noise.is_heard_at(position) {
+                continue;
+            }
+
+            // how loud it is for the bot
+            let volume = 1.0 - position.distance(noise.position) / noise.loudness;
+
+            if heard.is_none_or(|h| volume > h.1) {
+                heard = Some((noise.position, volume));
+            }
+        }
+
+        if let Some((source, _)) = heard {
+            if DEBUG {
+                debug_line(position, source, PURPLE);
+            }
+
+            bot.last_known = Some((source, time));
+        }
+    }
+}
@@ -1,23 +1,32 @@
-use super::component::BotShootingState;
+use super::{
+    behaviour::{Behaviour, Situation},
+    component::BotShootingState,
+};
 use crate::{
     plugin::{
-        bot::{Bot, BotConfig},
+        bot::{Bot, BotConfig, BotOrder},
         debug::{debug_circle, debug_line},
         kinetics::Kinetics,
-        Actor, ActorAction, Weapon,
+        navigation::{Navigation, Path},
+        scenario::FriendlyFire,
+        Actor, ActorAction, Bonus, FireMode, Health, Obstacle, Weapon,
     },
     util::{
         ext::{QuatExt, Vec2Ext},
+        geometry::GeometryProjection,
         math::angle_difference,
         traits::{WithPosition, WithPositionAndVelocity, WithVelocity},
     },
 };
 use bevy::{
     color::{
-        palettes::css::{GREEN, ORANGE, RED, WHITE, YELLOW},
+        palettes::css::{AQUA, BLUE, GREEN, ORANGE, RED, WHITE, YELLOW},
         Alpha,
     },
-    ecs::system::Res,
+    ecs::{
+        entity::Entity,
+        system::{Res, ResMut},
+    },
     math::{Vec2, Vec3Swizzles},
     prelude::{Query, Transform, With},
     time::Time,
@@ -32,16 +41,30 @@ const DEBUG_TEAMMATES: bool = false;
 const DEBUG_AIM: bool = false;
 const DEBUG_SPREAD: bool = false;
 const DEBUG_DETOUR: bool = false;
+const DEBUG_PATH: bool = false;
+const DEBUG_COVER: bool = false;
 
 pub fn on_update(
-    mut bots: Query<(&mut Bot, &mut Actor, &Transform, &Kinetics, Option<&Weapon>)>,
+    mut bots: Query<(
+        Entity,
+        &mut Bot,
+        &mut Actor,
+        &Transform,
+        &Kinetics,
+        Option<&mut Weapon>,
+        Option<&Health>,
+    )>,
     actors: Query<(&Transform, &Kinetics), With<Actor>>,
+    bonuses: Query<&Transform, With<Bonus>>,
+    obstacles: Query<(&Transform, &Obstacle)>,
+    mut navigation: ResMut<Navigation>,
+    friendly_fire: Res<FriendlyFire>,
     time: Res<Time>,
 ) {
     crate::util::bench::bench!();
     let time = time.elapsed();
 
-    for (mut bot, mut actor, transform, kinetics, weapon) in bots.iter_mut() {
+    for (entity, mut bot, mut actor, transform, kinetics, mut weapon, health) in bots.iter_mut() {
         actor.reset_actions();
 
         let enemy = bot
@@ -57,46 +80,204 @@ pub fn on_update(
             // enemy no longer exists. force new enemy search now
             let reaction = bot.config.reaction;
             bot.enemy = None;
+            bot.last_known = None;
             bot.update_timer.set(time + reaction);
         }
 
+        let leader = bot
+            .leader
+            .and_then(|l| actors.get(l).ok())
+            .map(|l| l.0.translation.xy());
+
+        if bot.leader.is_some() && leader.is_none() {
+            // the leader is gone, so are its orders
+            bot.leader = None;
+            bot.order = None;
+        }
+
+        let pickup = bot
+            .pickup
+            .and_then(|b| bonuses.get(b).ok())
+            .map(|b| b.translation.xy());
+
+        if pickup.is_none() {
+            // picked up by someone or expired
+            bot.pickup = None;
+        }
+
+        let position = transform.translation.xy();
+
+        if let Some(enemy) = &enemy {
+            bot.last_known = Some((enemy.position, time));
+        }
+
+        let situation = Situation {
+            health: health.map_or(1.0, Health::get),
+            ammo: weapon.as_deref().map(|w| w.get_ammo_normalized(time)),
+            teammates: bot.teammates.len(),
+            enemy_proximity: enemy.as_ref().map(|e| {
+                let distance = position.distance(e.position);
+                return 1.0 - (distance / bot.config.shoot_distance_max).clamp(0.0, 1.0);
+            }),
+            enemy_threat: enemy.as_ref().map_or(0.0, |e| e.threat_to(position)),
+            has_last_known: bot.last_known.is_some(),
+            has_pickup: pickup.is_some(),
+            has_leader: leader.is_some(),
+            order: bot.order,
+        };
+
+        let behaviour = Behaviour::choose(&situation, &bot.config.weights);
+
+        let is_ally_in_line_of_fire = friendly_fire.is_on()
+            && enemy.as_ref().is_some_and(|e| {
+                return is_ally_in_line_of_fire(&bot, position, e.position, &actors);
+            });
+
+        let cover = enemy
+            .as_ref()
+            .filter(|_| {
+                bot.config.takes_cover && weapon.as_deref().is_some_and(Weapon::is_reloading)
+            })
+            .and_then(|e| find_cover(position, e.position, &obstacles));
+
         let mut handler = BotHandler {
+            entity,
             bot: &mut bot,
             actor: &mut actor,
             transform,
             velocity: kinetics.velocity,
-            weapon,
+            weapon: weapon.as_deref(),
+            navigation: &mut navigation,
             spread_out: SpreadOut::Default,
-            is_dodging: false,
+            is_ally_in_line_of_fire,
+            is_holding: matches!(situation.order, Some(BotOrder::Hold(_))),
+            cover,
         };
 
-        if let Some(enemy) = enemy {
-            if handler.bot.config.is_agile {
-                handler.dodge_enemy(&enemy);
-            }
-
-            if !handler.is_dodging {
-                handler.attack_enemy(&enemy, time);
-            }
-        } else {
+        if handler
+            .run(behaviour, enemy.as_ref(), leader, pickup, time)
+            .is_none()
+        {
             handler.idle();
         }
 
         handler.spread_out(&actors);
+
+        // switched at once, since the selector would fire a shot in the old mode between presses
+        if let Some(weapon) = weapon.as_mut() {
+            weapon.set_fire_mode(bot.fire_mode);
+        }
     }
 }
 
+/// Whether a teammate stands between the bot and its target
+fn is_ally_in_line_of_fire(
+    bot: &Bot,
+    from: Vec2,
+    to: Vec2,
+    actors: &Query<(&Transform, &Kinetics), With<Actor>>,
+) -> bool {
+    return bot
+        .teammates
+        .iter()
+        .filter_map(|t| actors.get(*t).ok())
+        .any(|(t, _)| {
+            let position = t.translation.xy();
+            let closest = position.project_on_clamped(&(from, to));
+            return closest.is_close(position, BotConfig::LINE_OF_FIRE_CLEARANCE);
+        });
+}
+
+/// The closest spot behind an obstacle, which hides from the enemy
+fn find_cover(
+    position: Vec2,
+    enemy: Vec2,
+    obstacles: &Query<(&Transform, &Obstacle)>,
+) -> Option<Vec2> {
+    let mut cover: Option<(Vec2, f32)> = None;
+
+    for (transform, obstacle) in obstacles.iter() {
+        let center = transform.translation.xy();
+
+        if center.is_far(position, BotConfig::COVER_DISTANCE) {
+            continue;
+        }
+
+        let spot = center
+            + (center - enemy).normalize_or_zero()
+                * (obstacle.bounding_radius() + BotConfig::COVER_GAP);
+
+        let distance = spot.distance_squared(position);
+
+        if cover.is_none_or(|c| distance < c.1) {
+            cover = Some((spot, distance));
+        }
+    }
+
+    if DEBUG_COVER {
+        if let Some((spot, _)) = cover {
+            debug_line(position, spot, BLUE);
+        }
+    }
+
+    return cover.map(|c| c.0);
+}
+
 struct BotHandler<'a> {
+    entity: Entity,
     bot: &'a mut Bot,
     actor: &'a mut Actor,
     transform: &'a Transform,
     velocity: Vec2,
     weapon: Option<&'a Weapon>,
+    navigation: &'a mut Navigation,
     spread_out: SpreadOut,
-    is_dodging: bool,
+    is_ally_in_line_of_fire: bool,
+    /// Ordered to stay in place, so doesn't chase enemies
+    is_holding: bool,
+    /// Where to hide from the enemy
+    cover: Option<Vec2>,
 }
 
 impl BotHandler<'_> {
+    /// Does the behaviour. Returns none if it lacks a target, which shouldn't happen once it's
+    /// chosen
+    fn run(
+        &mut self,
+        behaviour: Behaviour,
+        enemy: Option<&BotTarget>,
+        leader: Option<Vec2>,
+        pickup: Option<Vec2>,
+        time: Duration,
+    ) -> Option<()> {
+        match behaviour {
+            Behaviour::Idle => self.idle(),
+            Behaviour::Search => self.search(self.bot.last_known?.0, time),
+            Behaviour::Follow => self.follow(leader?, time),
+            Behaviour::Hold => {
+                let Some(BotOrder::Hold(position)) = self.bot.order else {
+                    return None;
+                };
+
+                self.hold(position, time);
+            }
+            Behaviour::Advance => {
+                let Some(BotOrder::Attack(target)) = self.bot.order else {
+                    return None;
+                };
+
+                self.advance(target, time);
+            }
+            Behaviour::Pickup => self.go_to(pickup?, time),
+            Behaviour::Attack => self.attack_enemy(enemy?, time),
+            Behaviour::Dodge => self.dodge_enemy(enemy?),
+            Behaviour::Retreat => self.retreat(enemy?, time),
+            Behaviour::Regroup => self.regroup(leader?, time),
+        }
+
+        return Some(());
+    }
+
     fn dodge_enemy(&mut self, enemy: &BotTarget) {
         let bot_to_enemy = self.direction_to(enemy);
         let enemy_to_bot = angle_difference(enemy.direction, enemy.direction_to(self));
@@ -117,7 +298,6 @@ impl BotHandler<'_> {
             self.look_at_direction(bot_to_enemy + turn * force);
             self.actor.movement += Vec2::FRONT;
             self.spread_out.set(SpreadOut::Compact);
-            self.is_dodging = true;
         }
     }
 
@@ -125,7 +305,7 @@ impl BotHandler<'_> {
         if let Some(weapon) = self.weapon {
             self.attack_enemy_armed(enemy, weapon, time);
         } else {
-            self.attack_enemy_melee(enemy);
+            self.attack_enemy_melee(enemy, time);
         }
     }
 
@@ -137,6 +317,10 @@ impl BotHandler<'_> {
         }
 
         if self.is_reloading() {
+            if let Some(cover) = self.cover {
+                self.take_cover(cover, target);
+            }
+
             return;
         }
 
@@ -144,6 +328,7 @@ impl BotHandler<'_> {
             self.spread_out.set(SpreadOut::Simplified);
 
             if self.bot.config.is_silly
+                && !self.is_holding
                 && self.is_far(&target.position, self.bot.config.shoot_distance_min * 1.25)
             {
                 // shoot while walking
@@ -152,16 +337,18 @@ impl BotHandler<'_> {
 
             self.bot.set_shooting_target(true, time);
 
-            let shooting_state = self
-                .bot
-                .get_shooting_state(weapon.config.is_automatic, time);
+            let distance = self.position().distance(target.position);
+            let shooting_state = self.bot.get_shooting_state(weapon.config, distance, time);
 
             let debug_color;
             let is_aimed = self.is_aimed_at_point(target.position);
 
-            if shooting_state == BotShootingState::Shoot && (is_aimed || self.bot.was_burst_fire) {
+            if shooting_state == BotShootingState::Shoot
+                && (is_aimed || self.bot.was_burst_fire)
+                && !self.is_ally_in_line_of_fire
+            {
                 self.actor.actions |= ActorAction::Attack;
-                self.bot.was_burst_fire = weapon.config.is_automatic;
+                self.bot.was_burst_fire = weapon.fire_mode() != FireMode::Semi;
                 debug_color = RED;
             } else {
                 // keep aim ony while not attacking, otherwise recoil won't work
@@ -194,13 +381,16 @@ impl BotHandler<'_> {
                     debug_color,
                 );
             }
+        } else if self.is_holding {
+            self.bot.set_shooting_target(false, time);
+            self.look_at_position(target.position);
         } else {
             self.bot.set_shooting_target(false, time);
-            self.chase(target);
+            self.chase(target, time);
         }
     }
 
-    fn attack_enemy_melee(&mut self, target: &BotTarget) {
+    fn attack_enemy_melee(&mut self, target: &BotTarget, time: Duration) {
         // TODO: count enemy body radius instead of self
         let melee_distance = self.actor.config.melee_distance + self.actor.config.radius;
 
@@ -210,14 +400,27 @@ impl BotHandler<'_> {
             self.actor.actions |= ActorAction::Attack;
             self.actor.movement += Vec2::FRONT;
             self.spread_out.set(SpreadOut::Disallowed);
+        } else if self.is_holding {
+            self.look_at_position(target.position);
         } else {
             // otherwise just chase
-            self.chase(target);
+            self.chase(target, time);
         }
     }
 
     #[expect(clippy::needless_late_init, reason = "I like that more here")]
-    fn chase(&mut self, target: &BotTarget) {
+    fn chase(&mut self, target: &BotTarget, time: Duration) {
+        if let Some(waypoint) = self.find_waypoint(target.position, time) {
+            if self.can_sprint() && self.is_far(&target.position, self.bot.config.sprint_distance) {
+                self.actor.actions |= ActorAction::Sprint;
+            }
+
+            self.spread_out.set(SpreadOut::Compact);
+            self.look_at_position(waypoint);
+            self.actor.movement += Vec2::FRONT;
+            return;
+        }
+
         let meet = self.find_meet(target);
         let target = target.position;
         let detour;
@@ -273,6 +476,142 @@ impl BotHandler<'_> {
         }
     }
 
+    /// The next waypoint of a path around obstacles, unless the target is in sight
+    fn find_waypoint(&mut self, target: Vec2, time: Duration) -> Option<Vec2> {
+        let position = self.position();
+
+        if let Some(path) = self.navigation.take_path(self.entity) {
+            self.bot.path = Some(path);
+        }
+
+        if self.navigation.is_line_clear(position, target) {
+            self.bot.path = None;
+            return None;
+        }
+
+        let is_outdated = self
+            .bot
+            .path
+            .as_ref()
+            .and_then(Path::destination)
+            .is_none_or(|d| d.is_far(target, BotConfig::PATH_DESTINATION_SHIFT));
+
+        let is_waiting = self.bot.path_timer.is_enabled() && !self.bot.path_timer.is_ready(time);
+
+        if is_outdated && !is_waiting {
+            // the old path is still followed till the new one is found
+            self.bot
+                .path_timer
+                .set(time + BotConfig::PATH_UPDATE_INTERVAL);
+            self.navigation.request_path(self.entity, position, target);
+        }
+
+        let path = self.bot.path.as_mut()?;
+
+        if DEBUG_PATH {
+            let mut p0 = position;
+
+            for p1 in path.waypoints() {
+                debug_line(p0, p1, AQUA.with_alpha(0.5));
+                p0 = p1;
+            }
+        }
+
+        return path.next(position);
+    }
+
+    /// Backs off the enemy while shooting at it, unless it hides
+    fn retreat(&mut self, enemy: &BotTarget, time: Duration) {
+        self.attack_enemy(enemy, time);
+
+        if !self.is_reloading() || self.cover.is_none() {
+            self.actor.movement = Vec2::BACK;
+            self.spread_out.set(SpreadOut::Disallowed);
+        }
+    }
+
+    /// Goes to where the enemy was seen the last time, and gives up there
+    fn search(&mut self, position: Vec2, time: Duration) {
+        self.bot.set_shooting_target(false, time);
+
+        if self.is_close(&position, BotConfig::SEARCH_REACH) {
+            self.bot.last_known = None;
+            self.bot.path = None;
+            self.idle();
+        } else {
+            self.go_to(position, time);
+        }
+    }
+
+    /// Goes to the ordered position, and follows the leader once it's there
+    fn advance(&mut self, position: Vec2, time: Duration) {
+        if self.is_close(&position, BotConfig::SEARCH_REACH) {
+            self.bot.order = Some(BotOrder::Follow);
+            self.bot.path = None;
+            self.idle();
+        } else {
+            self.go_to(position, time);
+        }
+    }
+
+    /// Returns to the held position, and watches around there
+    fn hold(&mut self, position: Vec2, time: Duration) {
+        if self.is_far(&position, BotConfig::SEARCH_REACH) {
+            self.go_to(position, time);
+        } else if let Some((last_known, _)) = self.bot.last_known {
+            self.look_at_position(last_known);
+        } else {
+            self.look_at_direction(self.bot.idle_direction);
+        }
+    }
+
+    fn follow(&mut self, leader: Vec2, time: Duration) {
+        if self.is_far(&leader, BotConfig::FOLLOW_DISTANCE) {
+            self.go_to(leader, time);
+        } else {
+            self.idle();
+        }
+    }
+
+    /// Runs to the leader, not being distracted by enemies
+    fn regroup(&mut self, leader: Vec2, time: Duration) {
+        if self.is_close(&leader, BotConfig::REGROUP_REACH) {
+            self.bot.order = Some(BotOrder::Follow);
+            self.idle();
+            return;
+        }
+
+        self.go_to(leader, time);
+
+        if self.can_sprint() {
+            self.actor.actions |= ActorAction::Sprint;
+        }
+    }
+
+    fn go_to(&mut self, position: Vec2, time: Duration) {
+        self.bot.set_shooting_target(false, time);
+
+        let target = BotTarget {
+            position,
+            velocity: Vec2::ZERO,
+            direction: 0.0,
+        };
+
+        self.chase(&target, time);
+    }
+
+    /// Moves to the cover while keeping an eye on the target
+    fn take_cover(&mut self, cover: Vec2, target: &BotTarget) {
+        self.look_at_position(target.position);
+        self.spread_out.set(SpreadOut::Disallowed);
+
+        if self.is_far(&cover, BotConfig::COVER_GAP) {
+            self.actor.movement = (cover - self.position())
+                .normalize_or_zero()
+                .rotate_by_quat(self.transform.rotation.inverse());
+        }
+    }
+
     fn idle(&mut self) {
         self.look_at_direction(self.bot.idle_direction);
 
@@ -433,6 +772,14 @@ pub struct BotTarget {
     pub direction: f32,
 }
 
+impl BotTarget {
+    /// How straight it faces the point, from `0.0` out of the dodge angle to `1.0`
+    fn threat_to(&self, point: Vec2) -> f32 {
+        let angle = angle_difference(self.direction, self.direction_to(&point));
+        return f32::max(1.0 - angle.abs() / BotConfig::DODGE_ANGLE, 0.0);
+    }
+}
+
 impl WithPosition for BotTarget {
     fn position(&self) -> Vec2 {
         return self.position;
@@ -1,22 +1,25 @@
 use crate::{
     data::VIEW_DISTANCE,
-    plugin::{camera::MainCamera, collision::CollisionSystems},
+    plugin::{
+        camera::MainCamera,
+        collision::CollisionSystems,
+        player::{PlayerInput, PlayerSystems},
+    },
     state::AppState,
-    util::ext::{AppExt, DurationExt, QuatExt},
+    util::ext::{DurationExt, QuatExt},
 };
 use bevy::{
-    app::{App, Plugin},
+    app::{App, FixedUpdate, Plugin},
     camera::Projection,
     ecs::{
         component::Component,
         schedule::IntoScheduleConfigs,
         system::{Query, Res},
     },
-    input::mouse::MouseWheel,
-    math::{Quat, Vec2},
-    prelude::{MessageReader, Transform, With, Without},
+    math::{Quat, Vec2, Vec3Swizzles},
+    prelude::{Transform, With, Without},
+    state::condition::in_state,
     time::Time,
-    window::{PrimaryWindow, Window},
 };
 use std::{
     f32::consts::FRAC_PI_2,
@@ -27,12 +30,21 @@ use std::{
 const SHAKE_Y: f32 = 0.004;
 const SHAKE_Z: f32 = 0.0006;
 const SHAKE_R: f32 = 0.0007;
+/// Distance from the outermost target to the view edge when several targets share the camera
+const SHARED_MARGIN: f32 = 4.0;
 
 pub struct CameraTargetPlugin;
 
 impl Plugin for CameraTargetPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(AppState::Game, on_update.after(CollisionSystems));
+        // it runs on a network client too, which has no simulation of its own
+        app.add_systems(
+            FixedUpdate,
+            on_update
+                .after(PlayerSystems)
+                .after(CollisionSystems)
+                .run_if(in_state(AppState::Game)),
+        );
     }
 }
 
@@ -67,51 +79,74 @@ impl CameraTarget {
     }
 }
 
+/// Follows the only target, or shows all the targets at once if there are several of them
 pub fn on_update(
     mut cameras: Query<(&mut Transform, &mut Projection), With<MainCamera>>,
     mut targets: Query<(&Transform, &mut CameraTarget), Without<MainCamera>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    mut mouse_scroll: MessageReader<MouseWheel>,
+    input: Res<PlayerInput>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
-    let mut zoom = 0.0;
+    let inputs = input.current();
+    let window_size = inputs.first().map_or(Vec2::ZERO, |i| i.viewport);
 
-    for event in mouse_scroll.read() {
-        zoom += event.y;
+    if window_size.x <= 0.0 || window_size.y <= 0.0 {
+        return;
     }
 
-    let window_size = if let Some(window) = windows.iter().next() {
-        Vec2::new(window.width(), window.height())
-    } else {
-        return;
-    };
+    let zoom = inputs.iter().map(|i| i.zoom).sum();
+    let mut positions = Vec::new();
+    let mut shake_push = Vec2::ZERO;
+    let mut shake_spin = 0.0;
 
-    if let Some((target_transform, mut target)) = targets.iter_mut().next() {
+    for (target_transform, mut target) in targets.iter_mut() {
         target.update(zoom, delta);
+        positions.push(target_transform.translation.xy());
+        shake_push += target.shake_push.get();
+        shake_spin += target.shake_spin.get();
+    }
 
-        let scale = VIEW_DISTANCE
-            / window_size.length()
-            / (target.zoom.get() - target.shake_push.get().length() * SHAKE_Z * target.zoom.get());
+    let Some((target_transform, mut target)) = targets.iter_mut().next() else {
+        return;
+    };
 
-        if let Some(offset_r) = target.sync_angle {
-            target.direction = target_transform.rotation.angle_z() - FRAC_PI_2 - offset_r;
-        }
+    let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+    let is_shared = positions.len() > 1;
+
+    let mut scale = VIEW_DISTANCE
+        / window_size.length()
+        / (target.zoom.get() - shake_push.length() * SHAKE_Z * target.zoom.get());
+
+    if is_shared {
+        // zoom out to keep everyone in the view, which doesn't rotate anymore
+        let extent = positions
+            .iter()
+            .map(|p| p.distance(center))
+            .fold(0.0, f32::max);
+        scale = f32::max(
+            scale,
+            (extent + SHARED_MARGIN) / window_size.min_element() * 2.0,
+        );
+    } else if let Some(offset_r) = target.sync_angle {
+        target.direction = target_transform.rotation.angle_z() - FRAC_PI_2 - offset_r;
+    }
 
-        let rotation = Quat::from_rotation_z(target.direction + target.shake_spin.get() * SHAKE_R);
+    let rotation = Quat::from_rotation_z(target.direction + shake_spin * SHAKE_R);
+    let mut offset = Vec2::ZERO;
+
+    if !is_shared {
+        offset = rotation.as_vec().perp() * window_size.y / 2.0 * scale * target.offset_y();
+    }
 
-        let mut offset = rotation.as_vec().perp();
-        offset *= window_size.y / 2.0 * scale * target.offset_y();
-        offset += target.shake_push.get() * SHAKE_Y;
+    offset += shake_push * SHAKE_Y;
 
-        if let Some((mut camera_transform, mut camera_projection)) = cameras.iter_mut().next() {
-            camera_transform.translation.x = target_transform.translation.x + offset.x;
-            camera_transform.translation.y = target_transform.translation.y + offset.y;
-            camera_transform.rotation = rotation;
+    if let Some((mut camera_transform, mut camera_projection)) = cameras.iter_mut().next() {
+        camera_transform.translation.x = center.x + offset.x;
+        camera_transform.translation.y = center.y + offset.y;
+        camera_transform.rotation = rotation;
 
-            if let Projection::Orthographic(camera_projection) = camera_projection.as_mut() {
-                camera_projection.scale = scale;
-            }
+        if let Projection::Orthographic(camera_projection) = camera_projection.as_mut() {
+            camera_projection.scale = scale;
         }
     }
 }
@@ -3,19 +3,24 @@ use crate::{
     plugin::{
         debug::{debug_circle, debug_line},
         kinetics::{self, Kinetics},
+        Obstacle,
     },
+    resource::RngResource,
     state::AppState,
-    util::ext::{AppExt, Vec2Ext},
+    util::{
+        ext::{AppExt, Vec2Ext},
+        Transform2D,
+    },
 };
 use bevy::{
     color::{
         palettes::css::{RED, WHITE, YELLOW},
         Alpha, Srgba,
     },
-    ecs::{component::Component, schedule::SystemSet, system::Local},
+    ecs::{component::Component, query::Without, schedule::SystemSet, system::Local},
     math::Vec2,
     platform::collections::HashMap,
-    prelude::{App, Entity, IntoScheduleConfigs, Plugin, Query, Transform},
+    prelude::{App, Entity, IntoScheduleConfigs, Plugin, Query, ResMut, Transform},
 };
 use rand::RngExt;
 use std::f32::consts::TAU;
@@ -30,9 +35,10 @@ pub struct CollisionSystems;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(
+        app.add_state_system_fixed(
             AppState::Game,
-            on_update
+            (on_update, on_update_obstacles)
+                .chain()
                 .in_set(CollisionSystems)
                 .after(kinetics::on_update),
         );
@@ -47,6 +53,7 @@ pub struct Collision {
 fn on_update(
     mut query: Query<(Entity, &mut Transform, &Collision, &mut Kinetics)>,
     mut cache: Local<SpatialIndex>,
+    mut rng: ResMut<RngResource>,
 ) {
     crate::util::bench::bench!();
 
@@ -73,7 +80,7 @@ fn on_update(
 
             if distance.is_short(distance_min) {
                 let angle = if distance.is_zero() {
-                    Vec2::from_angle(rand::rng().random_range(0.0..TAU))
+                    Vec2::from_angle(rng.random_range(0.0..TAU))
                 } else {
                     distance.normalize()
                 };
@@ -112,20 +119,103 @@ fn on_update(
         });
     }
 }
-#[derive(Default)]
-struct SpatialIndex {
-    chunks: HashMap<SpatialId, Vec<SpatialData>>,
+
+/// Obstacles go last, so bodies pushed by each other don't end up inside them
+fn on_update_obstacles(
+    mut bodies: Query<(&mut Transform, &Collision, &mut Kinetics), Without<Obstacle>>,
+    obstacles: Query<(Entity, &Transform, &Obstacle)>,
+    mut cache: Local<SpatialIndex<Entity>>,
+    mut nearby: Local<Vec<Entity>>,
+) {
+    crate::util::bench::bench!();
+    cache.chunks.clear();
+
+    for (entity, transform, obstacle) in obstacles.iter() {
+        let position = transform.translation.truncate();
+
+        if DEBUG {
+            debug_circle(position, obstacle.bounding_radius(), WHITE);
+        }
+
+        cache.insert_area(position, obstacle.bounding_radius(), entity);
+    }
+
+    for (mut transform, collision, mut kinetics) in bodies.iter_mut() {
+        // a big obstacle takes several chunks, so it may be found more than once
+        nearby.clear();
+        cache.iter_area(transform.translation.truncate(), collision.radius, |e| {
+            nearby.push(*e);
+        });
+        nearby.sort_unstable();
+        nearby.dedup();
+
+        for (_, obstacle_transform, obstacle) in obstacles.iter_many(nearby.iter()) {
+            let position = transform.translation.truncate();
+            let obstacle_position = obstacle_transform.translation.truncate();
+
+            if position.is_far(
+                obstacle_position,
+                obstacle.bounding_radius() + collision.radius,
+            ) {
+                continue;
+            }
+
+            let (distance, normal) =
+                obstacle.contact(Transform2D::from(obstacle_transform), position);
+
+            if distance >= collision.radius {
+                continue;
+            }
+
+            let shift = normal * (collision.radius - distance + EXTRA_RESOLVE_DISTANCE);
+            transform.translation.x += shift.x;
+            transform.translation.y += shift.y;
+
+            // only the velocity towards the obstacle is lost, so the body slides along it
+            let velocity_into = kinetics.velocity.dot(normal);
+
+            if velocity_into < 0.0 {
+                kinetics.velocity -= normal * velocity_into;
+            }
+
+            if DEBUG {
+                debug_circle(position, collision.radius, RED);
+            }
+        }
+    }
+}
+
+struct SpatialIndex<T = SpatialData> {
+    chunks: HashMap<SpatialId, Vec<T>>,
+}
+
+impl<T> Default for SpatialIndex<T> {
+    fn default() -> Self {
+        return Self {
+            chunks: HashMap::default(),
+        };
+    }
 }
 
-impl SpatialIndex {
-    fn insert(&mut self, position: Vec2, value: SpatialData) {
+impl<T: Copy> SpatialIndex<T> {
+    fn insert(&mut self, position: Vec2, value: T) {
         self.chunks
             .entry(SpatialId::from(position))
             .or_insert_with(|| Vec::with_capacity(8))
             .push(value);
     }
 
-    fn pop(&mut self) -> Option<SpatialData> {
+    /// Inserts the value into each chunk the circle touches
+    fn insert_area(&mut self, position: Vec2, radius: f32, value: T) {
+        for id in SpatialId::range(position, radius) {
+            self.chunks
+                .entry(id)
+                .or_insert_with(|| Vec::with_capacity(8))
+                .push(value);
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
         let mut value = None;
         let mut empty_chunk_id = None;
 
@@ -148,7 +238,7 @@ impl SpatialIndex {
         return value;
     }
 
-    fn iter_neighbors<F: FnMut(&SpatialData)>(&self, position: Vec2, mut f: F) {
+    fn iter_neighbors<F: FnMut(&T)>(&self, position: Vec2, mut f: F) {
         let origin = SpatialId::from(position);
         let offset_direction = SpatialId {
             x: calc_offset_direction(position.x),
@@ -169,7 +259,14 @@ impl SpatialIndex {
         }
     }
 
-    fn iter_chunk<F: FnMut(&SpatialData)>(&self, id: &SpatialId, f: &mut F) {
+    /// Iterates values of each chunk the circle touches
+    fn iter_area<F: FnMut(&T)>(&self, position: Vec2, radius: f32, mut f: F) {
+        for id in SpatialId::range(position, radius) {
+            self.iter_chunk(&id, &mut f);
+        }
+    }
+
+    fn iter_chunk<F: FnMut(&T)>(&self, id: &SpatialId, f: &mut F) {
         if let Some(chunk) = self.chunks.get(id) {
             for item in chunk.iter() {
                 f(item);
@@ -185,6 +282,13 @@ struct SpatialId {
 }
 
 impl SpatialId {
+    /// Chunks which a circle touches
+    fn range(position: Vec2, radius: f32) -> impl Iterator<Item = Self> {
+        let min = Self::from(position - radius);
+        let max = Self::from(position + radius);
+        return (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| Self { x, y }));
+    }
+
     fn draw_debug(&self, color: Srgba) {
         let v = |x: i32, y: i32| Vec2::new(x as f32, y as f32);
         let p = v(self.x, self.y);
@@ -227,4 +331,21 @@ mod tests {
         assert_eq!(calc_offset_direction(-12.45), 1);
         assert_eq!(calc_offset_direction(-12.55), -1);
     }
+
+    #[test]
+    fn spatial_area() {
+        let mut index = SpatialIndex::<u8>::default();
+        index.insert_area(Vec2::ZERO, 1.5, 1);
+
+        let find = |position: Vec2| {
+            let mut found = Vec::new();
+            index.iter_area(position, 0.2, |v| found.push(*v));
+            return found;
+        };
+
+        // the value is met in each chunk both circles touch
+        assert!(find(Vec2::new(-1.9, 0.0)).contains(&1));
+        assert!(find(Vec2::new(1.0, 1.0)).contains(&1));
+        assert!(find(Vec2::new(3.0, 0.0)).is_empty());
+    }
 }
@@ -13,7 +13,6 @@ use bevy::{
         component::Component,
         entity::Entity,
         query::{With, Without},
-        schedule::IntoScheduleConfigs,
         system::{Commands, Query},
         world::World,
     },
@@ -33,10 +32,7 @@ pub struct CrosshairPlugin;
 impl Plugin for CrosshairPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<CrosshairMaterial>::default());
-        app.add_state_system(
-            AppState::Game,
-            on_update.after(crate::plugin::camera_target::on_update),
-        );
+        app.add_state_system(AppState::Game, on_update);
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::{
     plugin::{
-        bot::ActorBotSet, ActorConfig, ActorKind, ActorSet, AudioTracker, BonusSpawn, Crosshair,
-        Explode, ProjectileConfig, TileMap, WeaponConfig, WeaponSet,
+        bot::ActorBotSet, ActorConfig, ActorRegistry, ActorSet, AudioTracker, BonusSpawn,
+        Crosshair, Explode, ProjectileConfig, TileMap, WeaponRegistry, WeaponSet,
     },
     state::AppState,
     util::{ext::AppExt, Timer, Transform2D},
@@ -28,7 +28,7 @@ use bevy::{
     transform::components::Transform,
     ui::widget::{Text, TextUiWriter},
 };
-use rand::seq::IndexedRandom;
+use rand::seq::IteratorRandom;
 use std::{
     sync::{Mutex, OnceLock},
     time::Duration,
@@ -94,6 +94,7 @@ fn on_init(world: &mut World) {
             \nSpawn weapon: [G]\
             \nSpawn human : [H] group: [+SHIFT]\
             \nSpawn zombie: [J] group: [+SHIFT]\
+            \nSpawn bandit: [K] group: [+SHIFT]\
             \nExplode: [T]\
             ",
         ));
@@ -192,6 +193,8 @@ fn render_debug_shapes(mut gizmos: Gizmos) {
 fn update_input(
     crosshairs: Query<&Transform, With<Crosshair>>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    actors: Res<ActorRegistry>,
+    weapons: Res<WeaponRegistry>,
     mut commands: Commands,
 ) {
     let spawn = if keyboard.just_pressed(KeyCode::KeyG) {
@@ -200,6 +203,8 @@ fn update_input(
         Spawn::Human
     } else if keyboard.just_pressed(KeyCode::KeyJ) {
         Spawn::Zombie
+    } else if keyboard.just_pressed(KeyCode::KeyK) {
+        Spawn::Bandit
     } else if keyboard.just_pressed(KeyCode::KeyT) {
         Spawn::Explosion
     } else {
@@ -223,10 +228,19 @@ fn update_input(
             commands.queue(BonusSpawn::new(position.position, u8::MAX));
         }
         Spawn::Human => {
-            spawn_actors(&mut commands, position, &ActorConfig::HUMAN, group);
+            if let Some(config) = actors.get("human") {
+                spawn_actors(&mut commands, &weapons, position, config, group);
+            }
         }
         Spawn::Zombie => {
-            spawn_actors(&mut commands, position, &ActorConfig::ZOMBIE, group);
+            if let Some(config) = actors.get("zombie") {
+                spawn_actors(&mut commands, &weapons, position, config, group);
+            }
+        }
+        Spawn::Bandit => {
+            if let Some(config) = actors.get("bandit") {
+                spawn_actors(&mut commands, &weapons, position, config, group);
+            }
         }
         Spawn::Explosion => {
             if let Some(explosion) = &ProjectileConfig::TBG_7V.explosion {
@@ -242,6 +256,7 @@ fn update_input(
 
 fn spawn_actors(
     commands: &mut Commands,
+    weapons: &WeaponRegistry,
     transform: Transform2D,
     config: &'static ActorConfig,
     group: u8,
@@ -258,9 +273,10 @@ fn spawn_actors(
 
         commands.queue(ActorBotSet { entity });
 
-        let weapon = match config.kind {
-            ActorKind::Human => WeaponConfig::ALL.choose(&mut rand::rng()),
-            ActorKind::Zombie => None,
+        let weapon = if config.kind.is_human() {
+            weapons.available().choose(&mut rand::rng())
+        } else {
+            None
         };
 
         commands.queue(WeaponSet { entity, weapon });
@@ -271,6 +287,7 @@ enum Spawn {
     Bonus,
     Human,
     Zombie,
+    Bandit,
     Explosion,
 }
 
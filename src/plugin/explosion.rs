@@ -2,9 +2,10 @@ use crate::{
     data::{LAYER_GROUND, LAYER_PROJECTILE},
     plugin::{
         collision::{Collision, CollisionSystems},
-        Actor, AudioPlay, AudioTracker, ProjectileExplosion, TileBlend,
+        scenario::FriendlyFire,
+        Actor, AudioPlay, AudioTracker, Noise, ProjectileExplosion, TileBlend,
     },
-    resource::{AssetStorage, HitResource},
+    resource::{AssetStorage, HitResource, RngResource},
     state::AppState,
     util::ext::{AppExt, Fuzz, Vec2Ext},
 };
@@ -39,7 +40,7 @@ pub struct ExplosionPlugin;
 impl Plugin for ExplosionPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<ExplosionMaterial>::default());
-        app.add_state_system(AppState::Game, on_update.after(CollisionSystems));
+        app.add_state_system_fixed(AppState::Game, on_update.after(CollisionSystems));
     }
 }
 
@@ -77,23 +78,29 @@ impl Command for Explode {
             ))
             .insert(explosion);
 
-        world.resource::<AudioTracker>().queue(AudioPlay {
+        let sound = AudioPlay {
             path: "sounds/explosion".into(),
             volume: 1.2,
             source: Some(self.position),
             falloff: AudioPlay::FALLOFF_LONGEST,
             ..AudioPlay::DEFAULT
-        });
+        };
+
+        world.write_message_batch(Noise::from_audio(&sound, self.shooter));
+        world.resource::<AudioTracker>().queue(sound);
 
-        let mut rng = rand::rng();
+        let mut rng = world.resource_mut::<RngResource>();
+        let direction = rng.random_range(0.0..TAU);
+        let size = self.config.radius.fuzz_with(&mut *rng, 0.2);
+        let flip = rng.random();
 
         TileBlend::Image {
             image: "terrain/crater.png",
             color: WHITE.with_alpha(0.8).into(),
             position: self.position.extend(LAYER_GROUND),
-            direction: rng.random_range(0.0..TAU),
-            size: self.config.radius.fuzz_with(&mut rng, 0.2),
-            flip: rng.random(),
+            direction,
+            size,
+            flip,
         }
         .apply(world);
     }
@@ -134,6 +141,7 @@ fn on_update(
     mut assets: ResMut<Assets<ExplosionMaterial>>,
     mut hits: Deferred<HitResource>,
     mut commands: Commands,
+    friendly_fire: Res<FriendlyFire>,
     time: Res<Time>,
 ) {
     let now = time.elapsed();
@@ -171,11 +179,11 @@ fn on_update(
             .map(|a| a.1.config.kind);
 
         for (actor_entity, actor, actor_transform, actor_body) in actors.iter() {
-            if explosion.shooter == Some(actor_entity) {
-                continue;
-            }
+            // the shooter gets hurt by own explosion in full anyway
+            let is_ally =
+                explosion.shooter != Some(actor_entity) && shooter_kind == Some(actor.config.kind);
 
-            if shooter_kind == Some(actor.config.kind) {
+            if is_ally && !friendly_fire.is_on() {
                 continue;
             }
 
@@ -186,10 +194,20 @@ fn on_update(
                     continue;
                 }
 
-                let energy = (actor_position - explosion_position).normalize()
+                let mut energy = (actor_position - explosion_position).normalize_or_zero()
                     * explosion.config.energy
                     * force_factor;
 
+                if is_ally {
+                    log::info!(
+                        "{:?} hit an ally {} by friendly fire",
+                        explosion.shooter,
+                        actor_entity,
+                    );
+
+                    energy *= friendly_fire.damage_factor();
+                }
+
                 hits.add(actor_entity, energy, 0.0, false);
                 hits.add(actor_entity, energy * PUSH_MULTIPLIER, 0.0, true); // extra push without damage
                 explosion.damaged.push(actor_entity);
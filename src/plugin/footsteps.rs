@@ -1,5 +1,6 @@
 use crate::{
-    plugin::{AudioPlay, AudioTracker},
+    plugin::{AudioPlay, AudioTracker, Noise},
+    resource::RngResource,
     state::AppState,
     util::{
         ext::{AppExt, Fuzz},
@@ -11,11 +12,13 @@ use bevy::{
     app::{App, Plugin},
     ecs::{
         component::Component,
+        entity::Entity,
+        message::MessageWriter,
         schedule::IntoScheduleConfigs,
         system::{Local, Query},
     },
     math::Vec2,
-    prelude::{Res, Time, Transform},
+    prelude::{Res, ResMut, Time, Transform},
 };
 use rand::RngExt;
 use std::time::Duration;
@@ -36,7 +39,7 @@ pub struct FootstepsPlugin;
 
 impl Plugin for FootstepsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(
+        app.add_state_system_fixed(
             AppState::Game,
             on_update.run_if(|mut r: Local<Timer>, t: Res<Time>| {
                 return r.try_next_set(t.elapsed(), || BUFFER_DURATION);
@@ -53,19 +56,21 @@ pub struct Footsteps {
 
 // TODO: play sound on turn
 fn on_update(
-    mut query: Query<(&mut Footsteps, &Transform)>,
+    mut query: Query<(Entity, &mut Footsteps, &Transform)>,
+    mut noises: MessageWriter<Noise>,
     audio: Res<AudioTracker>,
     time: Res<Time>,
+    mut rng: ResMut<RngResource>,
 ) {
     crate::util::bench::bench!();
     let time = time.elapsed();
     let mut combined_volume = 0.0;
 
-    for (mut footsteps, transform) in query.iter_mut() {
+    for (entity, mut footsteps, transform) in query.iter_mut() {
         let position = transform.translation.truncate();
 
         if footsteps.time.is_zero() {
-            let offset_ms = rand::rng().random_range(0..500);
+            let offset_ms = rng.random_range(0..500);
             let offset = Duration::from_millis(offset_ms);
             footsteps.time = time + offset;
             footsteps.position = position;
@@ -97,15 +102,28 @@ fn on_update(
         footsteps.position = position;
 
         let volume_abstract = calc_stride_volume(intensity);
+
+        let sound = AudioPlay {
+            volume: volume_abstract,
+            source: Some(position),
+            ..SOUND
+        };
+
+        noises.write_batch(Noise::from_audio(&sound, Some(entity)));
+
         let volume_spatial = SOUND.calc_spatial_volume(volume_abstract, position, audio.listener);
 
         combined_volume += volume_spatial * volume_spatial;
     }
 
+    // the volume depends on the listener which isn't a part of the game logic, so generate the
+    // speed anyway to keep the random sequence same
+    let speed = 1.0.fuzz_with(&mut *rng, 0.1);
+
     if combined_volume > AudioPlay::VOLUME_MIN * AudioPlay::VOLUME_MIN {
         audio.queue(AudioPlay {
             volume: f32::min(combined_volume.sqrt(), 1.0),
-            speed: 1.0.fuzz_with(&mut rand::rng(), 0.1),
+            speed,
             ..SOUND
         });
     }
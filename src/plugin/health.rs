@@ -1,11 +1,14 @@
 use crate::{
     plugin::{
         player::Player, Actor, ActorDeathEvent, ActorRelease, AudioPlay, AudioTracker, BloodSpawn,
-        FleshParticleSpawn,
+        BonusSpawn, FleshParticleSpawn, Inventory, Weapon,
     },
     resource::{ScenarioSettings, Settings},
     state::AppState,
-    util::{ext::AppExt, Timer},
+    util::{
+        ext::{AppExt, Vec2Ext},
+        Timer,
+    },
 };
 use bevy::{
     app::{App, Plugin},
@@ -26,12 +29,14 @@ const BUFFERING: Duration = Duration::from_millis(80);
 const LOW_VALUE: f32 = 0.4;
 const FLESH_PARTICLE_PER_DAMAGE: f32 = 0.2;
 const FLESH_PARTICLES_MAX: i32 = 8;
+/// How far from a dead actor the weapons of its inventory fall
+const INVENTORY_DROP_DISTANCE: f32 = 0.4;
 
 pub struct HealthPlugin;
 
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(
+        app.add_state_system_fixed(
             AppState::Game,
             on_update.run_if(|mut r: Local<Timer>, t: Res<Time>| {
                 return r.try_next_set(t.elapsed(), || BUFFERING);
@@ -84,6 +89,11 @@ impl Health {
         }
     }
 
+    /// Unlike `heal`, sets health as is. Mind that it doesn't make the actor die
+    pub fn set(&mut self, health: f32) {
+        self.health = health.clamp(0.0, 1.0);
+    }
+
     pub fn multiply_resistance(&mut self, n: f32) {
         self.resistance *= n;
     }
@@ -92,6 +102,10 @@ impl Health {
         return self.health;
     }
 
+    pub fn resistance(&self) -> f32 {
+        return self.resistance;
+    }
+
     pub fn is_alive(&self) -> bool {
         return self.health > 0.0;
     }
@@ -102,7 +116,15 @@ impl Health {
 }
 
 fn on_update(
-    mut query: Query<(Entity, &Actor, &mut Health, &Transform, Has<Player>)>,
+    mut query: Query<(
+        Entity,
+        &Actor,
+        &mut Health,
+        &Transform,
+        Option<&Weapon>,
+        Option<&Inventory>,
+        Has<Player>,
+    )>,
     mut death_events: MessageWriter<ActorDeathEvent>,
     mut commands: Commands,
     settings: Res<Settings>,
@@ -113,7 +135,7 @@ fn on_update(
         return;
     }
 
-    for (entity, actor, mut health, transform, is_player) in query.iter_mut() {
+    for (entity, actor, mut health, transform, weapon, inventory, is_player) in query.iter_mut() {
         let actor = actor.config;
         let point = transform.translation.xy();
 
@@ -144,6 +166,17 @@ fn on_update(
 
             commands.queue(ActorRelease(entity));
 
+            if let Some(weapon) = weapon {
+                commands.queue(BonusSpawn::dropped(point, weapon.config, weapon.ammo()));
+            }
+
+            let stored = inventory.map_or(&[][..], |i| i.stored());
+
+            for (i, (weapon, ammo)) in stored.iter().enumerate() {
+                let offset = Vec2::from_length(INVENTORY_DROP_DISTANCE, i as f32 * 2.0);
+                commands.queue(BonusSpawn::dropped(point + offset, weapon, *ammo));
+            }
+
             death_events.write(ActorDeathEvent {
                 kind: actor.kind,
                 position: point,
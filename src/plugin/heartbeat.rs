@@ -65,8 +65,6 @@ fn on_update(
                 interpolate(SPEED_MIN, SPEED_MAX, 1.0 - actor.stamina.powf(4.0)),
             );
             play = true;
-        } else {
-            play = false;
         }
     }
 
@@ -1,9 +1,17 @@
-use crate::resource::{Settings, WindowModeSettings};
+use crate::resource::{Binding, Control, Settings, Wheel, WindowModeSettings};
 use bevy::{
     app::{AppExit, Update},
-    ecs::{query::With, system::Command, world::World},
-    input::ButtonInput,
-    prelude::{App, Commands, KeyCode, Plugin, Res},
+    ecs::{
+        query::With,
+        system::{Command, Query},
+        world::World,
+    },
+    input::{
+        gamepad::{ButtonSettings, Gamepad, GamepadButton, GamepadSettings},
+        mouse::MouseWheel,
+        ButtonInput,
+    },
+    prelude::{App, Commands, DetectChanges, KeyCode, MessageReader, MouseButton, Plugin, Res},
     window::{CursorGrabMode, CursorOptions, PrimaryWindow, Window},
 };
 
@@ -11,21 +19,72 @@ pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, on_update);
+        app.add_systems(Update, (on_update, configure_gamepads));
     }
 }
 
-fn on_update(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>) {
-    if keyboard.just_pressed(KeyCode::Escape) {
+/// Button states of the devices which a player uses, to check the bound controls
+#[derive(Default, Clone, Copy)]
+pub struct Buttons<'a> {
+    pub keyboard: Option<&'a ButtonInput<KeyCode>>,
+    pub mouse: Option<&'a ButtonInput<MouseButton>>,
+    /// Vertical scroll of the mouse wheel during the frame
+    pub wheel: f32,
+    pub gamepad: Option<&'a Gamepad>,
+}
+
+impl Buttons<'_> {
+    pub fn pressed(&self, bindings: &[Binding]) -> bool {
+        return bindings.iter().any(|binding| match *binding {
+            Binding::Key(key) => self.keyboard.is_some_and(|k| k.pressed(key)),
+            Binding::Mouse(button) => self.mouse.is_some_and(|m| m.pressed(button)),
+            Binding::Wheel(direction) => self.is_scrolled(direction),
+            Binding::Gamepad(button) => self.gamepad.is_some_and(|g| g.pressed(button)),
+        });
+    }
+
+    pub fn just_pressed(&self, bindings: &[Binding]) -> bool {
+        return bindings.iter().any(|binding| match *binding {
+            Binding::Key(key) => self.keyboard.is_some_and(|k| k.just_pressed(key)),
+            Binding::Mouse(button) => self.mouse.is_some_and(|m| m.just_pressed(button)),
+            Binding::Wheel(direction) => self.is_scrolled(direction),
+            Binding::Gamepad(button) => self.gamepad.is_some_and(|g| g.just_pressed(button)),
+        });
+    }
+
+    fn is_scrolled(&self, direction: Wheel) -> bool {
+        return match direction {
+            Wheel::Up => self.wheel > 0.0,
+            Wheel::Down => self.wheel < 0.0,
+        };
+    }
+}
+
+fn on_update(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut mouse_scroll: MessageReader<MouseWheel>,
+    settings: Res<Settings>,
+) {
+    let bindings = &settings.controls.bindings;
+    let buttons = Buttons {
+        keyboard: Some(&keyboard),
+        mouse: Some(&mouse),
+        wheel: mouse_scroll.read().map(|e| e.y).sum(),
+        gamepad: None,
+    };
+
+    if buttons.just_pressed(bindings.get(Control::Exit)) {
         commands.write_message(AppExit::Success);
     }
 
-    if keyboard.just_pressed(KeyCode::Tab) {
+    if buttons.just_pressed(bindings.get(Control::ReleaseCursor)) {
         // TODO: hide cursor on widow click
         commands.queue(CursorGrab(false));
     }
 
-    if keyboard.just_pressed(KeyCode::F11) {
+    if buttons.just_pressed(bindings.get(Control::Fullscreen)) {
         commands.queue(|world: &mut World| {
             let mut settings = world.resource_mut::<Settings>();
 
@@ -53,6 +112,24 @@ fn on_update(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>) {
     }
 }
 
+/// Applies the trigger threshold to gamepads once they connect or the settings change
+fn configure_gamepads(mut gamepads: Query<&mut GamepadSettings>, settings: Res<Settings>) {
+    let threshold = settings.controls.gamepad_trigger_threshold.clamp(0.0, 1.0);
+
+    // a bit lower release threshold, so a trigger held near it doesn't flicker
+    let Ok(trigger) = ButtonSettings::new(threshold, threshold * 0.8) else {
+        return;
+    };
+
+    for mut gamepad in gamepads.iter_mut() {
+        if gamepad.is_added() || settings.is_changed() {
+            for button in [GamepadButton::LeftTrigger2, GamepadButton::RightTrigger2] {
+                gamepad.button_settings.insert(button, trigger.clone());
+            }
+        }
+    }
+}
+
 pub struct CursorGrab(pub bool);
 
 impl Command for CursorGrab {
@@ -9,7 +9,7 @@ pub struct KineticsPlugin;
 
 impl Plugin for KineticsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(AppState::Game, on_update);
+        app.add_state_system_fixed(AppState::Game, on_update);
     }
 }
 
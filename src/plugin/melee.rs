@@ -1,7 +1,7 @@
 use crate::{
     plugin::{
         collision::CollisionSystems, Actor, ActorAction, ActorActionsExt, ActorConfig, AudioPlay,
-        AudioTracker, Weapon,
+        AudioTracker, Noise, Weapon,
     },
     resource::HitResource,
     state::AppState,
@@ -11,7 +11,7 @@ use crate::{
     },
 };
 use bevy::{
-    ecs::{entity::Entity, system::Deferred, world::World},
+    ecs::{entity::Entity, message::MessageWriter, system::Deferred, world::World},
     prelude::{App, Commands, IntoScheduleConfigs, Plugin, Query, Res, Transform, Vec2, Without},
     time::Time,
 };
@@ -20,7 +20,7 @@ pub struct MeleePlugin;
 
 impl Plugin for MeleePlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(AppState::Game, on_update.after(CollisionSystems));
+        app.add_state_system_fixed(AppState::Game, on_update.after(CollisionSystems));
     }
 }
 
@@ -28,6 +28,7 @@ fn on_update(
     attackers: Query<(Entity, &Actor, &Transform), Without<Weapon>>,
     targets: Query<(Entity, &Actor, &Transform)>,
     mut hits: Deferred<HitResource>,
+    mut noises: MessageWriter<Noise>,
     audio: Res<AudioTracker>,
     mut commands: Commands,
     time: Res<Time>,
@@ -57,12 +58,15 @@ fn on_update(
                 false,
             );
 
-            audio.queue(AudioPlay {
+            let sound = AudioPlay {
                 path: "sounds/melee".into(),
                 volume: 0.6,
                 source: Some(position),
                 ..AudioPlay::DEFAULT
-            });
+            };
+
+            noises.write_batch(Noise::from_audio(&sound, Some(entity)));
+            audio.queue(sound);
 
             commands.queue(move |world: &mut World| {
                 if let Some(mut actor) = world.get_mut::<Actor>(entity) {
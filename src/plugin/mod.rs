@@ -19,11 +19,19 @@ mod input;
 pub mod kinetics;
 mod loading;
 mod melee;
+pub mod navigation;
+pub mod net;
+mod noise;
+mod obstacle;
 mod particle;
 pub mod player;
 mod projectile;
+pub mod replay;
+pub mod save;
 pub mod scenario;
+pub mod simulation;
 mod skip_loader;
+mod smoothing;
 mod status_bar;
 mod terrain;
 mod tile_map;
@@ -33,6 +41,6 @@ mod weapon;
 pub use self::{
     actor::*, ambience_fx::*, audio::*, blood::*, bonus::*, breath::*, camera::*, crosshair::*,
     debug_tweaks::*, explosion::*, footsteps::*, health::*, heartbeat::*, input::*, loading::*,
-    melee::*, particle::*, projectile::*, skip_loader::*, status_bar::*, terrain::*, tile_map::*,
-    ui_notification::*, weapon::*,
+    melee::*, noise::*, obstacle::*, particle::*, projectile::*, skip_loader::*, smoothing::*,
+    status_bar::*, terrain::*, tile_map::*, ui_notification::*, weapon::*,
 };
@@ -0,0 +1,385 @@
+use crate::{
+    data::WORLD_SIZE,
+    plugin::Obstacle,
+    state::AppState,
+    util::{ext::AppExt, Transform2D},
+};
+use bevy::{
+    ecs::{
+        entity::Entity,
+        lifecycle::RemovedComponents,
+        query::Added,
+        resource::Resource,
+        schedule::{IntoScheduleConfigs, SystemSet},
+        system::{Query, ResMut},
+    },
+    math::Vec2,
+    platform::collections::HashMap,
+    prelude::{App, Plugin, Transform},
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+};
+use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+
+const CELL_SIZE: f32 = 0.5;
+const CELLS: usize = (WORLD_SIZE / CELL_SIZE) as usize;
+/// Obstacles are inflated by it, so a path doesn't lead too close to them
+const AGENT_RADIUS: f32 = 0.3;
+const COST_STRAIGHT: u32 = 10;
+const COST_DIAGONAL: u32 = 14;
+const WAYPOINT_REACH: f32 = CELL_SIZE;
+
+pub struct NavigationPlugin;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NavigationSystems;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Navigation>();
+        app.add_state_system_fixed(AppState::Game, on_update.in_set(NavigationSystems));
+    }
+}
+
+/// Finds paths around obstacles. Searches run in the background, but each one is finished on the
+/// next tick after the request, waiting for it if needed, so the simulation stays deterministic
+#[derive(Resource, Default)]
+pub struct Navigation {
+    grid: Arc<NavGrid>,
+    searches: Vec<(Entity, Task<Option<Path>>)>,
+    found: HashMap<Entity, Path>,
+}
+
+impl Navigation {
+    pub fn is_line_clear(&self, from: Vec2, to: Vec2) -> bool {
+        return self.grid.is_line_clear(from, to);
+    }
+
+    /// The result is taken by `take_path` on the next tick
+    pub fn request_path(&mut self, agent: Entity, from: Vec2, to: Vec2) {
+        let grid = Arc::clone(&self.grid);
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            return grid.find_path(from, to);
+        });
+
+        self.searches.push((agent, task));
+    }
+
+    /// A path found by the last request of the agent, unless there's no way to the destination
+    pub fn take_path(&mut self, agent: Entity) -> Option<Path> {
+        return self.found.remove(&agent);
+    }
+}
+
+/// Waypoints to go through one by one
+#[derive(Debug, Clone)]
+pub struct Path {
+    /// In reverse order, so the next one is popped from the end
+    waypoints: Vec<Vec2>,
+}
+
+impl Path {
+    pub fn destination(&self) -> Option<Vec2> {
+        return self.waypoints.first().copied();
+    }
+
+    /// The waypoint to go to from the given position, skipping the reached ones
+    pub fn next(&mut self, position: Vec2) -> Option<Vec2> {
+        while let Some(waypoint) = self.waypoints.last() {
+            if waypoint.distance(position) < WAYPOINT_REACH {
+                self.waypoints.pop();
+            } else {
+                return Some(*waypoint);
+            }
+        }
+
+        return None;
+    }
+
+    pub fn waypoints(&self) -> impl Iterator<Item = Vec2> + '_ {
+        return self.waypoints.iter().rev().copied();
+    }
+}
+
+/// Cells of the world which are blocked by obstacles
+pub struct NavGrid {
+    blocked: Vec<bool>,
+    is_empty: bool,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        return Self {
+            blocked: vec![false; CELLS * CELLS],
+            is_empty: true,
+        };
+    }
+}
+
+impl NavGrid {
+    pub fn new(obstacles: &[(Transform2D, Obstacle)]) -> Self {
+        let mut grid = Self::default();
+
+        for (transform, obstacle) in obstacles {
+            let reach = obstacle.bounding_radius() + AGENT_RADIUS;
+            let (x_min, y_min) = cell_of(transform.position - reach);
+            let (x_max, y_max) = cell_of(transform.position + reach);
+
+            for y in y_min..=y_max {
+                for x in x_min..=x_max {
+                    if obstacle.distance(*transform, center_of(x, y)) < AGENT_RADIUS {
+                        grid.blocked[y * CELLS + x] = true;
+                        grid.is_empty = false;
+                    }
+                }
+            }
+        }
+
+        return grid;
+    }
+
+    fn is_blocked(&self, x: usize, y: usize) -> bool {
+        return self.blocked[y * CELLS + x];
+    }
+
+    /// The cells of the ends are skipped, since an agent may stand close to an obstacle
+    pub fn is_line_clear(&self, from: Vec2, to: Vec2) -> bool {
+        if self.is_empty {
+            return true;
+        }
+
+        let ends = [cell_of(from), cell_of(to)];
+        let steps = (from.distance(to) / (CELL_SIZE / 2.0)).ceil() as usize;
+
+        for i in 0..=steps {
+            let (x, y) = cell_of(from.lerp(to, i as f32 / steps.max(1) as f32));
+
+            if !ends.contains(&(x, y)) && self.is_blocked(x, y) {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    /// A* over the cells. The start and destination cells are passable even if they are blocked,
+    /// since an agent may stand close to an obstacle
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Path> {
+        let start = cell_of(from);
+        let goal = cell_of(to);
+        let index = |(x, y): (usize, usize)| y * CELLS + x;
+
+        let mut costs = vec![u32::MAX; CELLS * CELLS];
+        let mut previous = vec![usize::MAX; CELLS * CELLS];
+        let mut open = BinaryHeap::new();
+
+        costs[index(start)] = 0;
+        open.push(Reverse((estimate(start, goal), index(start))));
+
+        while let Some(Reverse((_, current))) = open.pop() {
+            let cell = (current % CELLS, current / CELLS);
+
+            if cell == goal {
+                return Some(self.build_path(from, to, &previous, current));
+            }
+
+            for (dx, dy) in [
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ] {
+                let Some(neighbor) = offset(cell, dx, dy) else {
+                    continue;
+                };
+
+                if neighbor != goal && self.is_blocked(neighbor.0, neighbor.1) {
+                    continue;
+                }
+
+                let is_diagonal = dx != 0 && dy != 0;
+
+                // don't cut corners of obstacles
+                if is_diagonal
+                    && (self.is_blocked(neighbor.0, cell.1) || self.is_blocked(cell.0, neighbor.1))
+                {
+                    continue;
+                }
+
+                let step = if is_diagonal {
+                    COST_DIAGONAL
+                } else {
+                    COST_STRAIGHT
+                };
+
+                let cost = costs[current] + step;
+
+                if cost < costs[index(neighbor)] {
+                    costs[index(neighbor)] = cost;
+                    previous[index(neighbor)] = current;
+                    open.push(Reverse((cost + estimate(neighbor, goal), index(neighbor))));
+                }
+            }
+        }
+
+        return None;
+    }
+
+    /// Walks the found cells back and keeps only the waypoints where the direction has to change
+    fn build_path(&self, from: Vec2, to: Vec2, previous: &[usize], goal: usize) -> Path {
+        let mut cells = vec![to];
+        let mut current = previous[goal];
+
+        while current != usize::MAX {
+            cells.push(center_of(current % CELLS, current / CELLS));
+            current = previous[current];
+        }
+
+        // the start cell isn't needed, the agent is there already
+        cells.pop();
+
+        let mut waypoints = vec![to];
+        let mut anchor = to;
+
+        for i in 1..cells.len() {
+            if anchor != cells[i - 1] && !self.is_line_clear(anchor, cells[i]) {
+                anchor = cells[i - 1];
+                waypoints.push(anchor);
+            }
+        }
+
+        if !self.is_line_clear(anchor, from) {
+            if let Some(last) = cells.last() {
+                waypoints.push(*last);
+            }
+        }
+
+        return Path { waypoints };
+    }
+}
+
+fn on_update(
+    mut navigation: ResMut<Navigation>,
+    obstacles: Query<(&Transform, &Obstacle)>,
+    added: Query<(), Added<Obstacle>>,
+    mut removed: RemovedComponents<Obstacle>,
+) {
+    crate::util::bench::bench!();
+
+    if !added.is_empty() || removed.read().count() > 0 {
+        let obstacles = obstacles
+            .iter()
+            .map(|(t, o)| (Transform2D::from(t), *o))
+            .collect::<Vec<_>>();
+
+        navigation.grid = Arc::new(NavGrid::new(&obstacles));
+    }
+
+    let navigation = &mut *navigation;
+    navigation.found.clear();
+
+    for (agent, task) in navigation.searches.drain(..) {
+        if let Some(path) = block_on(task) {
+            navigation.found.insert(agent, path);
+        }
+    }
+}
+
+/// A point out of the world gets the closest cell
+fn cell_of(point: Vec2) -> (usize, usize) {
+    let cell = ((point + WORLD_SIZE / 2.0) / CELL_SIZE).floor();
+    let limit = (CELLS - 1) as f32;
+    return (
+        cell.x.clamp(0.0, limit) as usize,
+        cell.y.clamp(0.0, limit) as usize,
+    );
+}
+
+fn center_of(x: usize, y: usize) -> Vec2 {
+    return (Vec2::new(x as f32, y as f32) + 0.5) * CELL_SIZE - WORLD_SIZE / 2.0;
+}
+
+fn offset((x, y): (usize, usize), dx: isize, dy: isize) -> Option<(usize, usize)> {
+    let x = x.checked_add_signed(dx).filter(|x| *x < CELLS)?;
+    let y = y.checked_add_signed(dy).filter(|y| *y < CELLS)?;
+    return Some((x, y));
+}
+
+/// Octile distance, which never overestimates the cost with diagonal moves
+fn estimate(from: (usize, usize), to: (usize, usize)) -> u32 {
+    let dx = from.0.abs_diff(to.0) as u32;
+    let dy = from.1.abs_diff(to.1) as u32;
+    return COST_STRAIGHT * dx.max(dy) + (COST_DIAGONAL - COST_STRAIGHT) * dx.min(dy);
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    fn wall() -> NavGrid {
+        let transform = Transform2D {
+            position: Vec2::ZERO,
+            rotation: 0.0,
+        };
+
+        let wall = Obstacle::Box {
+            half_size: Vec2::new(0.5, 5.0),
+        };
+
+        return NavGrid::new(&[(transform, wall)]);
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let grid = wall();
+        assert!(!grid.is_line_clear(Vec2::new(-3.0, 0.0), Vec2::new(3.0, 0.0)));
+        assert!(grid.is_line_clear(Vec2::new(-3.0, 6.0), Vec2::new(3.0, 6.0)));
+        assert!(NavGrid::default().is_line_clear(Vec2::new(-3.0, 0.0), Vec2::new(3.0, 0.0)));
+    }
+
+    #[test]
+    fn path_around_wall() {
+        let grid = wall();
+        let from = Vec2::new(-3.0, 0.0);
+        let to = Vec2::new(3.0, 0.0);
+
+        let path = grid.find_path(from, to).unwrap();
+
+        let mut previous = from;
+
+        for waypoint in path.waypoints() {
+            assert!(grid.is_line_clear(previous, waypoint));
+            previous = waypoint;
+        }
+
+        assert_eq!(previous, to);
+        assert!(path.waypoints().count() > 1);
+    }
+
+    #[test]
+    fn no_path() {
+        let transform = Transform2D::default();
+        let ring = [
+            (Vec2::new(0.0, 2.0), Vec2::new(2.5, 0.5)),
+            (Vec2::new(0.0, -2.0), Vec2::new(2.5, 0.5)),
+            (Vec2::new(2.0, 0.0), Vec2::new(0.5, 2.5)),
+            (Vec2::new(-2.0, 0.0), Vec2::new(0.5, 2.5)),
+        ]
+        .map(|(position, half_size)| {
+            return (
+                Transform2D {
+                    position,
+                    ..transform
+                },
+                Obstacle::Box { half_size },
+            );
+        });
+
+        let grid = NavGrid::new(&ring);
+        assert!(grid.find_path(Vec2::ZERO, Vec2::new(6.0, 0.0)).is_none());
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{
+    plugin::{
+        net::{
+            message::{ActorSnapshot, ClientMessage, Control, ServerMessage, Snapshot},
+            DATAGRAM_SIZE_MAX, DEFAULT_PORT, SNAPSHOT_PERIOD, TIMEOUT,
+        },
+        player::{Player, PlayerInput, PlayerSet},
+        Actor, ActorAction, ActorRegistry, ActorRelease, ActorSet, AmmoReserve, BloodSpawn,
+        BonusSpawn, Health, Notify, Weapon, WeaponRegistry, WeaponSet,
+    },
+    util::{ext::QuatExt, math},
+};
+use anyhow::{Context, Result};
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Has, With},
+        resource::Resource,
+        system::{Command, Query, Res},
+        world::World,
+    },
+    math::{Quat, Vec2, Vec3Swizzles},
+    time::{Fixed, Time},
+    transform::components::Transform,
+};
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    net::{Ipv4Addr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+/// The server removes bonuses, so they never expire on a client
+const BONUS_LIFETIME: Duration = Duration::from_secs(60 * 60 * 24);
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
+
+#[derive(Resource)]
+pub struct NetClient {
+    socket: UdpSocket,
+    /// Id of the own actor, once the server welcomes the client
+    actor: Option<u64>,
+    /// Local entities by their ids on the server
+    entities: HashMap<u64, Entity>,
+    tick: Option<u32>,
+    last_received: Instant,
+    is_lost: bool,
+}
+
+/// Moves an entity smoothly from its previous position to the received one
+#[derive(Component)]
+pub(super) struct Interpolation {
+    position: (Vec2, Vec2),
+    rotation: (f32, f32),
+    started: Duration,
+}
+
+impl NetClient {
+    pub fn connect(address: &str) -> Result<Self> {
+        let context = || format!("Failed to connect to {}", address);
+
+        let address = if address.contains(':') {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, DEFAULT_PORT)
+        };
+
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).with_context(context)?;
+        socket.connect(&address).with_context(context)?;
+        socket.set_nonblocking(true).with_context(context)?;
+        log::info!("Connecting to {}", address);
+
+        return Ok(Self {
+            socket,
+            actor: None,
+            entities: HashMap::new(),
+            tick: None,
+            last_received: Instant::now(),
+            is_lost: false,
+        });
+    }
+
+    fn send(&self, message: &ClientMessage) {
+        if let Err(error) = self.socket.send(&message.encode()) {
+            log::debug!("Failed to send: {}", error);
+        }
+    }
+}
+
+impl Drop for NetClient {
+    fn drop(&mut self) {
+        // otherwise the server would wait for the timeout
+        self.send(&ClientMessage::Leave);
+    }
+}
+
+pub(super) fn send(
+    client: Res<NetClient>,
+    player_input: Option<Res<PlayerInput>>,
+    actors: Query<(&Actor, &Transform), With<Player>>,
+) {
+    if client.actor.is_none() {
+        client.send(&ClientMessage::Join);
+        return;
+    }
+
+    let Some((actor, transform)) = actors.iter().next() else {
+        // the actor is dead, but the client keeps watching
+        client.send(&ClientMessage::Control(Control::default()));
+        return;
+    };
+
+    let mut actions = actor.actions;
+    actions.remove(ActorAction::AimToggle); // aiming is done by the client
+
+    // a client has the only local player
+    let input = player_input.as_ref().and_then(|i| i.current().first());
+
+    client.send(&ClientMessage::Control(Control {
+        movement: actor.movement,
+        actions,
+        rotation: transform.rotation.angle_z(),
+        aim_distance: actor.aim_distance,
+        order: input.and_then(|i| i.order),
+        inventory: input.and_then(|i| i.inventory),
+    }));
+}
+
+pub(super) fn receive(world: &mut World) {
+    let time = world.resource::<Time>().elapsed();
+    let mut client = world.resource_mut::<NetClient>();
+    let mut buffer = vec![0; DATAGRAM_SIZE_MAX];
+    let mut snapshot = None;
+
+    loop {
+        let length = match client.socket.recv(&mut buffer) {
+            Ok(length) => length,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+            Err(error) => {
+                // the server isn't started yet, or it's gone
+                log::debug!("Failed to receive: {}", error);
+                break;
+            }
+        };
+
+        client.last_received = Instant::now();
+
+        match ServerMessage::decode(&buffer[..length]) {
+            Ok(ServerMessage::Welcome { actor }) => {
+                if client.actor.is_none() {
+                    log::info!("Joined the server");
+                    client.actor = Some(actor);
+                }
+            }
+            Ok(ServerMessage::Snapshot(received)) => {
+                // datagrams may come out of order, so only the latest one is taken
+                if client.tick.is_none_or(|t| received.tick > t) {
+                    client.tick = Some(received.tick);
+                    snapshot = Some(received);
+                }
+            }
+            Err(error) => {
+                log::debug!("Invalid message: {:?}", error);
+            }
+        }
+    }
+
+    let is_lost = client.last_received.elapsed() > TIMEOUT;
+
+    if is_lost != client.is_lost {
+        client.is_lost = is_lost;
+
+        if is_lost {
+            Notify {
+                text: "No connection".into(),
+                text_small: "The server doesn't respond".into(),
+                duration: NOTIFICATION_DURATION,
+            }
+            .apply(world);
+        }
+    }
+
+    if let Some(snapshot) = snapshot {
+        apply_snapshot(world, snapshot, time);
+    }
+}
+
+fn apply_snapshot(world: &mut World, snapshot: Snapshot, time: Duration) {
+    let mut client = world.resource_mut::<NetClient>();
+    let own = client.actor;
+    let mut previous = std::mem::take(&mut client.entities);
+    let mut entities = HashMap::with_capacity(previous.len());
+
+    for actor in &snapshot.actors {
+        let entity = previous
+            .remove(&actor.id)
+            .or_else(|| spawn_actor(world, actor, time));
+
+        if let Some(entity) = entity {
+            // the welcome might come after the actor is spawned already
+            if own == Some(actor.id) && world.get::<Player>(entity).is_none() {
+                PlayerSet {
+                    entity,
+                    index: 0,
+                    is_controllable: true,
+                }
+                .apply(world);
+            }
+
+            update_actor(world, entity, actor, time);
+            entities.insert(actor.id, entity);
+        }
+    }
+
+    for bonus in &snapshot.bonuses {
+        let entity = previous.remove(&bonus.id).or_else(|| {
+            let weapon = world.resource::<WeaponRegistry>().get(&bonus.weapon)?;
+            return BonusSpawn::with_weapon(bonus.position, weapon, BONUS_LIFETIME).spawn(world);
+        });
+
+        if let Some(entity) = entity {
+            entities.insert(bonus.id, entity);
+        }
+    }
+
+    // the rest are gone on the server
+    for entity in previous.into_values() {
+        if world.get::<Actor>(entity).is_some() {
+            ActorRelease(entity).apply(world);
+        }
+
+        if let Ok(entity_mut) = world.get_entity_mut(entity) {
+            entity_mut.despawn();
+        }
+    }
+
+    world.resource_mut::<NetClient>().entities = entities;
+}
+
+fn spawn_actor(world: &mut World, actor: &ActorSnapshot, time: Duration) -> Option<Entity> {
+    let config = world.resource::<ActorRegistry>().get(&actor.config)?;
+    let entity = world.spawn_empty().id();
+
+    ActorSet {
+        entity,
+        config,
+        position: actor.position,
+        rotation: actor.rotation,
+    }
+    .apply(world);
+
+    world.entity_mut(entity).insert(Interpolation {
+        position: (actor.position, actor.position),
+        rotation: (actor.rotation, actor.rotation),
+        started: time,
+    });
+
+    return Some(entity);
+}
+
+fn update_actor(world: &mut World, entity: Entity, actor: &ActorSnapshot, time: Duration) {
+    if let Some(transform) = world.get::<Transform>(entity) {
+        let position = transform.translation.xy();
+        let rotation = transform.rotation.angle_z();
+
+        if let Some(mut interpolation) = world.get_mut::<Interpolation>(entity) {
+            interpolation.position = (position, actor.position);
+            interpolation.rotation = (rotation, actor.rotation);
+            interpolation.started = time;
+        }
+    }
+
+    if let Some(mut health) = world.get_mut::<Health>(entity) {
+        let damage = health.get() - actor.health;
+        health.set(actor.health);
+
+        // there's no damage on a client, so only its result is shown
+        if let Some(blood) = BloodSpawn::new(actor.position, damage) {
+            blood.apply(world);
+        }
+    }
+
+    if let Some(mut component) = world.get_mut::<Actor>(entity) {
+        component.stamina = actor.stamina;
+    }
+
+    let weapon_name = actor.weapon.as_ref().map(|(name, ..)| name.as_str());
+    let weapon_current = world.get::<Weapon>(entity).map(|w| w.config.name.as_str());
+
+    if weapon_name != weapon_current {
+        WeaponSet {
+            entity,
+            weapon: weapon_name.and_then(|n| world.resource::<WeaponRegistry>().get(n)),
+        }
+        .apply(world);
+    }
+
+    if let Some((_, ammo, reserve)) = actor.weapon {
+        let time = world.resource::<Time<Fixed>>().elapsed();
+        let mut projectile = None;
+
+        if let Some(mut weapon) = world.get_mut::<Weapon>(entity) {
+            let mut state = weapon.get_state(time);
+            state.ammo = ammo;
+            weapon.set_state(&state, time);
+            projectile = Some(weapon.config.projectile);
+        }
+
+        if let (Some(projectile), Some(mut reserved)) =
+            (projectile, world.get_mut::<AmmoReserve>(entity))
+        {
+            reserved.set(projectile, reserve);
+        }
+    }
+}
+
+pub(super) fn interpolate(
+    mut query: Query<(&Interpolation, &mut Transform, Has<Player>)>,
+    time: Res<Time>,
+) {
+    for (interpolation, mut transform, is_own) in query.iter_mut() {
+        let elapsed = time.elapsed().saturating_sub(interpolation.started);
+        let progress = f32::min(elapsed.as_secs_f32() / SNAPSHOT_PERIOD.as_secs_f32(), 1.0);
+        let (position_from, position_to) = interpolation.position;
+        let position = position_from.lerp(position_to, progress);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        // the own actor is aimed by the client, so its rotation isn't taken from the server
+        if !is_own {
+            let (rotation_from, rotation_to) = interpolation.rotation;
+            let rotation =
+                rotation_from + math::angle_difference(rotation_from, rotation_to) * progress;
+            transform.rotation = Quat::from_rotation_z(rotation);
+        }
+    }
+}
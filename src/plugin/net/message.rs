@@ -0,0 +1,477 @@
+use crate::{
+    plugin::{bot::SquadOrder, ActorActions, InventoryCommand},
+    util::ByteReader,
+};
+use anyhow::{bail, ensure, Context, Result};
+use bevy::math::Vec2;
+
+/// Increase on any change of the messages, so different builds don't misread each other
+const PROTOCOL: u16 = 6;
+
+const TAG_JOIN: u8 = 0;
+const TAG_CONTROL: u8 = 1;
+const TAG_LEAVE: u8 = 2;
+const TAG_WELCOME: u8 = 3;
+const TAG_SNAPSHOT: u8 = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum ClientMessage {
+    /// Sent repeatedly till the server welcomes the client
+    Join,
+    Control(Control),
+    Leave,
+}
+
+/// What a client does with its actor during a tick. The rotation is sent as is, since the aim is
+/// done by the client
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Control {
+    pub movement: Vec2,
+    pub actions: ActorActions,
+    pub rotation: f32,
+    pub aim_distance: f32,
+    pub order: Option<SquadOrder>,
+    pub inventory: Option<InventoryCommand>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ServerMessage {
+    /// The actor is an id of the client actor in the snapshots
+    Welcome {
+        actor: u64,
+    },
+    Snapshot(Snapshot),
+}
+
+/// Full state of the things a client shows. It's sent as a whole each time, so a lost one doesn't
+/// matter
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Snapshot {
+    pub tick: u32,
+    pub actors: Vec<ActorSnapshot>,
+    pub bonuses: Vec<BonusSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActorSnapshot {
+    pub id: u64,
+    pub config: String,
+    pub position: Vec2,
+    pub rotation: f32,
+    pub health: f32,
+    pub stamina: f32,
+    /// Weapon config name, loaded ammo and the reserve of it
+    pub weapon: Option<(String, u8, u16)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BonusSnapshot {
+    pub id: u64,
+    pub weapon: String,
+    pub position: Vec2,
+}
+
+impl ClientMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = PROTOCOL.to_le_bytes().to_vec();
+
+        match self {
+            Self::Join => {
+                data.push(TAG_JOIN);
+            }
+            Self::Control(control) => {
+                data.push(TAG_CONTROL);
+                write_vec2(&mut data, control.movement);
+                data.push(control.actions.as_u8_truncated());
+                write_f32(&mut data, control.rotation);
+                write_f32(&mut data, control.aim_distance);
+
+                // zero is no order
+                data.push(control.order.map_or(0, |o| o as u8 + 1));
+
+                // zero is no inventory command
+                let inventory = control
+                    .inventory
+                    .and_then(|c| InventoryCommand::ALL.iter().position(|a| *a == c))
+                    .map_or(0, |i| i as u8 + 1);
+
+                data.push(inventory);
+            }
+            Self::Leave => {
+                data.push(TAG_LEAVE);
+            }
+        }
+
+        return data;
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = read_header(data)?;
+
+        let message = match reader.take_u8()? {
+            TAG_JOIN => Self::Join,
+            TAG_CONTROL => {
+                let movement = read_vec2(&mut reader)?;
+
+                let Some(actions) = ActorActions::try_from_u8(reader.take_u8()?) else {
+                    bail!("Unknown actions");
+                };
+
+                Self::Control(Control {
+                    movement,
+                    actions,
+                    rotation: reader.take_f32()?,
+                    aim_distance: reader.take_f32()?,
+                    order: match reader.take_u8()? {
+                        0 => None,
+                        n => Some(
+                            *SquadOrder::ALL
+                                .get(usize::from(n - 1))
+                                .context("Unknown squad order")?,
+                        ),
+                    },
+                    inventory: match reader.take_u8()? {
+                        0 => None,
+                        n => Some(
+                            *InventoryCommand::ALL
+                                .get(usize::from(n - 1))
+                                .context("Unknown inventory command")?,
+                        ),
+                    },
+                })
+            }
+            TAG_LEAVE => Self::Leave,
+            tag => bail!("Unknown client message {}", tag),
+        };
+
+        ensure!(reader.is_empty(), "Unexpected data after message");
+        return Ok(message);
+    }
+}
+
+impl ServerMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = PROTOCOL.to_le_bytes().to_vec();
+
+        match self {
+            Self::Welcome { actor } => {
+                data.push(TAG_WELCOME);
+                data.extend_from_slice(&actor.to_le_bytes());
+            }
+            Self::Snapshot(snapshot) => {
+                data.push(TAG_SNAPSHOT);
+                data.extend_from_slice(&snapshot.tick.to_le_bytes());
+                let length = write_length(&mut data, snapshot.actors.len());
+
+                for actor in snapshot.actors.iter().take(length) {
+                    write_actor(&mut data, actor);
+                }
+
+                let length = write_length(&mut data, snapshot.bonuses.len());
+
+                for bonus in snapshot.bonuses.iter().take(length) {
+                    write_bonus(&mut data, bonus);
+                }
+            }
+        }
+
+        return data;
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = read_header(data)?;
+
+        let message = match reader.take_u8()? {
+            TAG_WELCOME => Self::Welcome {
+                actor: u64::from_le_bytes(reader.take()?),
+            },
+            TAG_SNAPSHOT => {
+                let tick = u32::from_le_bytes(reader.take()?);
+                let mut actors = Vec::new();
+                let mut bonuses = Vec::new();
+
+                for _ in 0..u16::from_le_bytes(reader.take()?) {
+                    actors.push(ActorSnapshot {
+                        id: u64::from_le_bytes(reader.take()?),
+                        config: read_str(&mut reader)?,
+                        position: read_vec2(&mut reader)?,
+                        rotation: reader.take_f32()?,
+                        health: reader.take_f32()?,
+                        stamina: reader.take_f32()?,
+                        weapon: match reader.take_u8()? {
+                            0 => None,
+                            _ => Some((
+                                read_str(&mut reader)?,
+                                reader.take_u8()?,
+                                u16::from_le_bytes(reader.take()?),
+                            )),
+                        },
+                    });
+                }
+
+                for _ in 0..u16::from_le_bytes(reader.take()?) {
+                    bonuses.push(BonusSnapshot {
+                        id: u64::from_le_bytes(reader.take()?),
+                        weapon: read_str(&mut reader)?,
+                        position: read_vec2(&mut reader)?,
+                    });
+                }
+
+                Self::Snapshot(Snapshot {
+                    tick,
+                    actors,
+                    bonuses,
+                })
+            }
+            tag => bail!("Unknown server message {}", tag),
+        };
+
+        ensure!(reader.is_empty(), "Unexpected data after message");
+        return Ok(message);
+    }
+}
+
+impl Snapshot {
+    /// Drops entities farthest from the center till the encoded snapshot fits the size
+    pub fn fit(&mut self, center: Vec2, size: usize) {
+        let distance = |position: Vec2| position.distance_squared(center);
+        self.actors
+            .sort_by(|a, b| distance(a.position).total_cmp(&distance(b.position)));
+        self.bonuses
+            .sort_by(|a, b| distance(a.position).total_cmp(&distance(b.position)));
+
+        let empty = ServerMessage::Snapshot(Self::default()).encode();
+        let mut space = size.saturating_sub(empty.len());
+        let mut entity = Vec::new();
+        let mut actors = 0;
+        let mut bonuses = 0;
+
+        loop {
+            let actor = self.actors.get(actors);
+            let bonus = self.bonuses.get(bonuses);
+
+            entity.clear();
+
+            // the closest of the next actor and bonus goes first
+            let is_actor = match (actor, bonus) {
+                (Some(a), Some(b)) if distance(a.position) > distance(b.position) => {
+                    write_bonus(&mut entity, b);
+                    false
+                }
+                (Some(a), _) => {
+                    write_actor(&mut entity, a);
+                    true
+                }
+                (None, Some(b)) => {
+                    write_bonus(&mut entity, b);
+                    false
+                }
+                (None, None) => break,
+            };
+
+            if entity.len() > space {
+                break;
+            }
+
+            space -= entity.len();
+
+            if is_actor {
+                actors += 1;
+            } else {
+                bonuses += 1;
+            }
+        }
+
+        self.actors.truncate(actors);
+        self.bonuses.truncate(bonuses);
+    }
+}
+
+fn read_header(data: &[u8]) -> Result<ByteReader<'_>> {
+    let mut reader = ByteReader::new(data);
+    let protocol = u16::from_le_bytes(reader.take()?);
+
+    ensure!(
+        protocol == PROTOCOL,
+        "Unsupported protocol {}, expected {}",
+        protocol,
+        PROTOCOL,
+    );
+
+    return Ok(reader);
+}
+
+fn write_f32(data: &mut Vec<u8>, value: f32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_vec2(data: &mut Vec<u8>, value: Vec2) {
+    write_f32(data, value.x);
+    write_f32(data, value.y);
+}
+
+fn read_vec2(reader: &mut ByteReader) -> Result<Vec2> {
+    return Ok(Vec2::new(reader.take_f32()?, reader.take_f32()?));
+}
+
+fn write_actor(data: &mut Vec<u8>, actor: &ActorSnapshot) {
+    data.extend_from_slice(&actor.id.to_le_bytes());
+    write_str(data, &actor.config);
+    write_vec2(data, actor.position);
+    write_f32(data, actor.rotation);
+    write_f32(data, actor.health);
+    write_f32(data, actor.stamina);
+
+    if let Some((weapon, ammo, reserve)) = &actor.weapon {
+        data.push(1);
+        write_str(data, weapon);
+        data.push(*ammo);
+        data.extend_from_slice(&reserve.to_le_bytes());
+    } else {
+        data.push(0);
+    }
+}
+
+fn write_bonus(data: &mut Vec<u8>, bonus: &BonusSnapshot) {
+    data.extend_from_slice(&bonus.id.to_le_bytes());
+    write_str(data, &bonus.weapon);
+    write_vec2(data, bonus.position);
+}
+
+/// Lists longer than a datagram could hold are cut off. Returns the length to write
+fn write_length(data: &mut Vec<u8>, length: usize) -> usize {
+    let length = u16::try_from(length).unwrap_or(u16::MAX);
+    data.extend_from_slice(&length.to_le_bytes());
+    return usize::from(length);
+}
+
+/// Names are short, so a longer one is cut off
+fn write_str(data: &mut Vec<u8>, value: &str) {
+    let bytes = &value.as_bytes()[..value.len().min(usize::from(u8::MAX))];
+    data.push(bytes.len() as u8);
+    data.extend_from_slice(bytes);
+}
+
+fn read_str(reader: &mut ByteReader) -> Result<String> {
+    let length = reader.take_u8()?;
+    return Ok(std::str::from_utf8(reader.take_slice(usize::from(length))?)?.to_string());
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::plugin::ActorAction;
+
+    #[test]
+    fn client_roundtrip() {
+        let messages = [
+            ClientMessage::Join,
+            ClientMessage::Control(Control {
+                movement: Vec2::new(1.0, -0.5),
+                actions: ActorAction::Attack | ActorAction::Sprint,
+                rotation: 2.5,
+                aim_distance: 7.0,
+                order: Some(SquadOrder::Hold),
+                inventory: Some(InventoryCommand::Drop),
+            }),
+            ClientMessage::Leave,
+        ];
+
+        for message in messages {
+            let decoded = ClientMessage::decode(&message.encode()).ok();
+            assert_eq!(decoded, Some(message));
+        }
+    }
+
+    #[test]
+    fn server_roundtrip() {
+        let messages = [
+            ServerMessage::Welcome { actor: 42 },
+            ServerMessage::Snapshot(Snapshot {
+                tick: 300,
+                actors: vec![
+                    ActorSnapshot {
+                        id: 1,
+                        config: "human".into(),
+                        position: Vec2::new(3.0, -4.0),
+                        rotation: 1.0,
+                        health: 0.5,
+                        stamina: 0.25,
+                        weapon: Some(("AKS-74U".into(), 30, 90)),
+                    },
+                    ActorSnapshot {
+                        id: 2,
+                        config: "zombie".into(),
+                        position: Vec2::ZERO,
+                        rotation: 0.0,
+                        health: 1.0,
+                        stamina: 1.0,
+                        weapon: None,
+                    },
+                ],
+                bonuses: vec![BonusSnapshot {
+                    id: 3,
+                    weapon: "PM".into(),
+                    position: Vec2::new(-1.0, 2.0),
+                }],
+            }),
+        ];
+
+        for message in messages {
+            let decoded = ServerMessage::decode(&message.encode()).ok();
+            assert_eq!(decoded, Some(message));
+        }
+    }
+
+    #[test]
+    fn snapshot_fit() {
+        let actor = |id: u64| ActorSnapshot {
+            id,
+            config: "zombie".into(),
+            position: Vec2::new(id as f32, 0.0),
+            rotation: 0.0,
+            health: 1.0,
+            stamina: 1.0,
+            weapon: None,
+        };
+
+        let mut snapshot = Snapshot {
+            tick: 1,
+            actors: (0..100).rev().map(actor).collect(),
+            bonuses: vec![BonusSnapshot {
+                id: 100,
+                weapon: "PM".into(),
+                position: Vec2::new(2.5, 0.0),
+            }],
+        };
+
+        let size = ServerMessage::Snapshot(snapshot.clone()).encode().len() / 10;
+        snapshot.fit(Vec2::ZERO, size);
+
+        let encoded = ServerMessage::Snapshot(snapshot.clone()).encode();
+        assert!(encoded.len() <= size);
+        assert_eq!(snapshot.bonuses.len(), 1);
+        assert!(snapshot.actors.len() > 2);
+        assert!(snapshot
+            .actors
+            .iter()
+            .enumerate()
+            .all(|(i, a)| a.id == i as u64));
+    }
+
+    #[test]
+    fn unsupported_protocol() {
+        let mut data = ClientMessage::Join.encode();
+        data[0] = data[0].wrapping_add(1);
+
+        let error = format!("{:#}", ClientMessage::decode(&data).err().unwrap());
+        let expected = format!(
+            "Unsupported protocol {}, expected {}",
+            PROTOCOL + 1,
+            PROTOCOL,
+        );
+
+        assert_eq!(error, expected);
+    }
+}
@@ -0,0 +1,166 @@
+mod client;
+mod message;
+mod server;
+
+pub use self::{client::*, server::*};
+use crate::{
+    plugin::{
+        player::PlayerSystems,
+        simulation::{AuthoritySystems, TIMESTEP},
+    },
+    state::AppState,
+    util::ext::AppExt,
+};
+use bevy::{
+    app::{App, FixedLast, FixedUpdate, Plugin},
+    ecs::schedule::IntoScheduleConfigs,
+    state::condition::in_state,
+};
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 7777;
+/// Snapshots are sent once per a few ticks, since clients interpolate between them anyway
+const SNAPSHOT_INTERVAL: u32 = 3;
+const SNAPSHOT_PERIOD: Duration = TIMESTEP.saturating_mul(SNAPSHOT_INTERVAL);
+const DATAGRAM_SIZE_MAX: usize = 65_507;
+/// A client or server which sent nothing for this long is considered gone
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Plays a game over UDP. The server runs the whole simulation and sends snapshots of it, while
+/// clients only control their actors and show what they receive
+pub enum NetPlugin {
+    /// Listens on the given port
+    Server(u16),
+    /// Connects to the given address, the port is optional
+    Client(String),
+}
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        match self {
+            Self::Server(port) => match NetServer::bind(*port) {
+                Ok(server) => {
+                    app.insert_resource(server);
+                    app.add_state_system_fixed(
+                        AppState::Game,
+                        server::receive.before(PlayerSystems),
+                    );
+                    app.add_systems(FixedLast, server::send.run_if(in_state(AppState::Game)));
+                }
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    log::warn!("The game runs without network");
+                }
+            },
+            Self::Client(address) => match NetClient::connect(address) {
+                Ok(client) => {
+                    app.insert_resource(client);
+                    // the world of a client comes from the server, so it's never simulated here
+                    app.configure_sets(FixedUpdate, AuthoritySystems.run_if(|| false));
+                    app.add_systems(
+                        FixedUpdate,
+                        client::send
+                            .after(PlayerSystems)
+                            .run_if(in_state(AppState::Game)),
+                    );
+                    app.add_state_system(
+                        AppState::Game,
+                        (client::receive, client::interpolate).chain(),
+                    );
+                }
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    log::warn!("The game runs without network");
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headless,
+        plugin::{
+            player::{Input, Player, PlayerInput},
+            scenario::{RemoteScenario, Scenario, WavesScenario},
+            Actor, InventoryCommand, Weapon,
+        },
+        resource::{GameSettings, Settings},
+    };
+    use bevy::{ecs::query::With, prelude::World};
+
+    fn build(players: u8, scenario: Scenario, net: NetPlugin) -> App {
+        let settings = Settings {
+            game: GameSettings {
+                seed: 42,
+                players,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut app = headless::build(settings, scenario);
+        app.add_plugins(net);
+        return app;
+    }
+
+    fn count_players(world: &mut World) -> usize {
+        return world
+            .query_filtered::<(), With<Player>>()
+            .iter(world)
+            .count();
+    }
+
+    fn count_actors(world: &mut World) -> usize {
+        return world.query::<&Actor>().iter(world).count();
+    }
+
+    #[test]
+    fn loopback() {
+        let waves = Scenario::new(WavesScenario::new("default", 1));
+        let mut server = build(0, waves, NetPlugin::Server(0));
+        let port = server.world().resource::<NetServer>().port();
+
+        let address = format!("127.0.0.1:{}", port);
+        let mut client = build(1, Scenario::new(RemoteScenario), NetPlugin::Client(address));
+
+        for _ in 0..600 {
+            server.update();
+            client.update();
+        }
+
+        // the server spawns an actor for the client, and the client sees it as its player
+        assert_eq!(count_players(server.world_mut()), 1);
+        assert_eq!(count_players(client.world_mut()), 1);
+        assert!(count_actors(client.world_mut()) > 1);
+
+        // the held weapon is dropped on the server by the command of the client
+        let input = Input {
+            inventory: Some(InventoryCommand::Drop),
+            ..Default::default()
+        };
+
+        let mut player_input = PlayerInput::default();
+        player_input.replace_current(&[input]);
+        client.world_mut().insert_resource(player_input);
+
+        for _ in 0..10 {
+            server.update();
+            client.update();
+        }
+
+        let weapons = server
+            .world_mut()
+            .query_filtered::<(), (With<Player>, With<Weapon>)>()
+            .iter(server.world())
+            .count();
+
+        assert_eq!(weapons, 0);
+
+        drop(client);
+        server.update();
+        assert_eq!(count_players(server.world_mut()), 0);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    data::VIEW_DISTANCE,
+    plugin::{
+        camera_target::CameraTarget,
+        net::{
+            message::{ActorSnapshot, BonusSnapshot, ClientMessage, ServerMessage, Snapshot},
+            DATAGRAM_SIZE_MAX, SNAPSHOT_INTERVAL, TIMEOUT,
+        },
+        player::{PlayerSpawn, SquadOrderGive},
+        Actor, ActorRegistry, ActorRelease, AmmoReserve, Bonus, Crosshair, Health, InventoryUse,
+        Notify, Weapon, WeaponRegistry,
+    },
+    resource::Settings,
+    util::ext::QuatExt,
+};
+use anyhow::{Context, Result};
+use bevy::{
+    ecs::{
+        entity::Entity,
+        resource::Resource,
+        system::{Command, Query, ResMut},
+        world::World,
+    },
+    math::{Quat, Vec2, Vec3Swizzles},
+    transform::components::Transform,
+};
+use std::{
+    f32::consts::TAU,
+    io::ErrorKind,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+const CLIENTS_MAX: usize = 8;
+const PLAYER_ACTOR: &str = "human";
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
+/// A client can't aim farther than it sees
+const AIM_DISTANCE_MAX: f32 = VIEW_DISTANCE;
+
+#[derive(Resource)]
+pub struct NetServer {
+    socket: UdpSocket,
+    clients: Vec<RemoteClient>,
+    tick: u32,
+}
+
+struct RemoteClient {
+    address: SocketAddr,
+    actor: Entity,
+    last_seen: Instant,
+}
+
+impl NetServer {
+    pub fn bind(port: u16) -> Result<Self> {
+        let context = || format!("Failed to start server on port {}", port);
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port)).with_context(context)?;
+        socket.set_nonblocking(true).with_context(context)?;
+        log::info!("Server listens on port {}", port);
+
+        return Ok(Self {
+            socket,
+            clients: Vec::new(),
+            tick: 0,
+        });
+    }
+
+    /// The actual port, which differs from the requested one if it was zero
+    #[cfg(test)]
+    pub fn port(&self) -> u16 {
+        return self.socket.local_addr().map_or(0, |a| a.port());
+    }
+
+    fn find_client(&mut self, address: SocketAddr) -> Option<&mut RemoteClient> {
+        return self.clients.iter_mut().find(|c| c.address == address);
+    }
+
+    fn send(&self, address: SocketAddr, data: &[u8]) {
+        if let Err(error) = self.socket.send_to(data, address) {
+            log::debug!("Failed to send to {}: {}", address, error);
+        }
+    }
+}
+
+pub(super) fn receive(world: &mut World) {
+    // the simulation time may go faster than the real one, as it does without window
+    let time = Instant::now();
+    let mut buffer = vec![0; DATAGRAM_SIZE_MAX];
+
+    loop {
+        let (length, address) = match world.resource::<NetServer>().socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+            // some systems report that a previous datagram didn't reach a client this way
+            Err(error) if error.kind() == ErrorKind::ConnectionReset => continue,
+            Err(error) => {
+                log::warn!("Failed to receive: {}", error);
+                break;
+            }
+        };
+
+        match ClientMessage::decode(&buffer[..length]) {
+            Ok(message) => handle(world, address, message, time),
+            Err(error) => log::debug!("Invalid message from {}: {:?}", address, error),
+        }
+    }
+
+    let mut server = world.resource_mut::<NetServer>();
+    let mut gone = Vec::new();
+
+    server.clients.retain(|c| {
+        if time.duration_since(c.last_seen) > TIMEOUT {
+            gone.push((c.address, c.actor));
+            return false;
+        } else {
+            return true;
+        }
+    });
+
+    for (address, actor) in gone {
+        log::info!("{} timed out", address);
+        release(world, actor);
+    }
+}
+
+fn handle(world: &mut World, address: SocketAddr, message: ClientMessage, time: Instant) {
+    let mut server = world.resource_mut::<NetServer>();
+    let actor = server.find_client(address).map(|c| {
+        c.last_seen = time;
+        return c.actor;
+    });
+
+    match message {
+        ClientMessage::Join => {
+            // a client repeats joining till it's welcomed, so it might be joined already
+            let Some(actor) = actor.or_else(|| join(world, address, time)) else {
+                return;
+            };
+
+            let welcome = ServerMessage::Welcome {
+                actor: actor.to_bits(),
+            };
+
+            world
+                .resource::<NetServer>()
+                .send(address, &welcome.encode());
+        }
+        ClientMessage::Control(control) => {
+            let Some(actor) = actor else {
+                return;
+            };
+
+            let is_finite = control.movement.is_finite()
+                && control.rotation.is_finite()
+                && control.aim_distance.is_finite();
+
+            if !is_finite {
+                log::debug!("Invalid control from {}", address);
+                return;
+            }
+
+            if let Some(mut component) = world.get_mut::<Actor>(actor) {
+                component.movement = control.movement.clamp_length_max(1.0);
+                component.actions = control.actions;
+                component.aim_distance = control.aim_distance.clamp(0.0, AIM_DISTANCE_MAX);
+            }
+
+            if let Some(mut transform) = world.get_mut::<Transform>(actor) {
+                transform.rotation = Quat::from_rotation_z(control.rotation.rem_euclid(TAU));
+            }
+
+            if let Some(order) = control.order {
+                SquadOrderGive {
+                    leader: actor,
+                    order,
+                }
+                .apply(world);
+            }
+
+            if let Some(command) = control.inventory {
+                InventoryUse {
+                    entity: actor,
+                    command,
+                }
+                .apply(world);
+            }
+        }
+        ClientMessage::Leave => {
+            let Some(actor) = actor else {
+                return;
+            };
+
+            world
+                .resource_mut::<NetServer>()
+                .clients
+                .retain(|c| c.address != address);
+
+            log::info!("{} left", address);
+            release(world, actor);
+        }
+    }
+}
+
+fn join(world: &mut World, address: SocketAddr, time: Instant) -> Option<Entity> {
+    let clients = world.resource::<NetServer>().clients.len();
+
+    if clients >= CLIENTS_MAX {
+        log::warn!("{} can't join, the server is full", address);
+        return None;
+    }
+
+    // remote players are indexed after the local ones
+    let players = usize::from(world.resource::<Settings>().game.players);
+    let index = u8::try_from(players + clients).unwrap_or(u8::MAX);
+
+    let actor = PlayerSpawn {
+        config: world.resource::<ActorRegistry>().get(PLAYER_ACTOR),
+        weapon: world
+            .resource::<WeaponRegistry>()
+            .available()
+            .min_by_key(|w| w.level),
+        index,
+        is_controllable: false,
+    }
+    .spawn(world)?;
+
+    // the client has its own camera and crosshair
+    world.entity_mut(actor).remove::<CameraTarget>();
+    Crosshair::despawn(world, actor);
+
+    world
+        .resource_mut::<NetServer>()
+        .clients
+        .push(RemoteClient {
+            address,
+            actor,
+            last_seen: time,
+        });
+
+    log::info!("{} joined", address);
+
+    Notify {
+        text: "Player joined".into(),
+        text_small: address.to_string().into(),
+        duration: NOTIFICATION_DURATION,
+    }
+    .apply(world);
+
+    return Some(actor);
+}
+
+/// The actor of a client which is gone is removed, unless it's already dead
+fn release(world: &mut World, actor: Entity) {
+    if world.get::<Actor>(actor).is_some() {
+        ActorRelease(actor).apply(world);
+        world.entity_mut(actor).despawn();
+    }
+}
+
+pub(super) fn send(
+    mut server: ResMut<NetServer>,
+    actors: Query<(
+        Entity,
+        &Actor,
+        &Transform,
+        &Health,
+        Option<&Weapon>,
+        Option<&AmmoReserve>,
+    )>,
+    bonuses: Query<(Entity, &Bonus, &Transform)>,
+) {
+    server.tick = server.tick.wrapping_add(1);
+
+    if server.clients.is_empty() || !server.tick.is_multiple_of(SNAPSHOT_INTERVAL) {
+        return;
+    }
+
+    let mut snapshot = Snapshot {
+        tick: server.tick,
+        ..Default::default()
+    };
+
+    for (entity, actor, transform, health, weapon, reserve) in actors.iter() {
+        if !health.is_alive() {
+            continue; // it's going to be despawned anyway
+        }
+
+        snapshot.actors.push(ActorSnapshot {
+            id: entity.to_bits(),
+            config: actor.config.name.clone(),
+            position: transform.translation.xy(),
+            rotation: transform.rotation.angle_z(),
+            health: health.get(),
+            stamina: actor.stamina,
+            weapon: weapon.map(|w| {
+                let reserve = reserve.map_or(0, |r| r.get(w.config.projectile));
+                return (w.config.name.clone(), w.ammo(), reserve);
+            }),
+        });
+    }
+
+    for (entity, bonus, transform) in bonuses.iter() {
+        snapshot.bonuses.push(BonusSnapshot {
+            id: entity.to_bits(),
+            weapon: bonus.weapon.name.clone(),
+            position: transform.translation.xy(),
+        });
+    }
+
+    let data = ServerMessage::Snapshot(snapshot.clone()).encode();
+
+    if data.len() <= DATAGRAM_SIZE_MAX {
+        for client in &server.clients {
+            server.send(client.address, &data);
+        }
+
+        return;
+    }
+
+    log::error!(
+        "Snapshot of {} bytes doesn't fit a datagram, far entities are dropped",
+        data.len(),
+    );
+
+    // each client gets the entities closest to its own actor
+    for client in &server.clients {
+        let center = actors
+            .get(client.actor)
+            .map_or(Vec2::ZERO, |(_, _, t, ..)| t.translation.xy());
+
+        let mut snapshot = snapshot.clone();
+        snapshot.fit(center, DATAGRAM_SIZE_MAX);
+        server.send(client.address, &ServerMessage::Snapshot(snapshot).encode());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{plugin::AudioPlay, util::ext::Vec2Ext};
+use bevy::{
+    ecs::{entity::Entity, message::Message},
+    math::Vec2,
+};
+
+/// A sound which bots may hear. Unlike the sound itself, it doesn't depend on the listener, so
+/// it's a part of the game logic
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Noise {
+    pub position: Vec2,
+    /// How far it's heard
+    pub loudness: f32,
+    /// Who made it, if anyone
+    pub source: Option<Entity>,
+}
+
+impl Noise {
+    /// Sounds fading below that volume aren't heard
+    const VOLUME_MIN: f32 = 0.05;
+
+    /// The noise of a sound, unless the sound is too quiet or isn't positioned
+    pub fn from_audio(audio: &AudioPlay, source: Option<Entity>) -> Option<Self> {
+        let position = audio.source?;
+        let loudness = f32::ln(audio.volume / Self::VOLUME_MIN) / audio.falloff;
+
+        if loudness.is_nan() || loudness <= 0.0 {
+            return None;
+        }
+
+        return Some(Self {
+            position,
+            loudness,
+            source,
+        });
+    }
+
+    pub fn is_heard_at(&self, point: Vec2) -> bool {
+        return self.position.is_close(point, self.loudness);
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    fn noise(volume: f32, falloff: f32) -> Option<Noise> {
+        let audio = AudioPlay {
+            volume,
+            falloff,
+            source: Some(Vec2::ZERO),
+            ..AudioPlay::DEFAULT
+        };
+
+        return Noise::from_audio(&audio, None);
+    }
+
+    #[test]
+    fn loudness() {
+        let shot = noise(1.0, AudioPlay::FALLOFF_LONGER).unwrap();
+        let step = noise(0.15, AudioPlay::FALLOFF_SHORTEST).unwrap();
+
+        assert!(shot.loudness > step.loudness);
+        assert!(shot.is_heard_at(Vec2::new(20.0, 0.0)));
+        assert!(!step.is_heard_at(Vec2::new(20.0, 0.0)));
+        assert!(noise(0.04, AudioPlay::FALLOFF_SHORTEST).is_none());
+        assert!(Noise::from_audio(&AudioPlay::DEFAULT, None).is_none());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    data::LAYER_TREE,
+    util::{ext::Vec2Ext, geometry::GeometryProjection, Transform2D},
+};
+use bevy::{
+    asset::Assets,
+    color::Color,
+    ecs::{component::Component, system::Command, world::World},
+    math::{
+        primitives::{Circle, Rectangle},
+        Quat, Vec2,
+    },
+    mesh::{Mesh, Mesh2d},
+    sprite_render::{ColorMaterial, MeshMaterial2d},
+    transform::components::Transform,
+};
+
+/// A static body of the map, placed and turned by the entity transform
+#[derive(Component, Debug, Clone, Copy)]
+pub enum Obstacle {
+    Circle { radius: f32 },
+    Box { half_size: Vec2 },
+}
+
+/// What an obstacle is made of, which decides how projectiles bounce off it
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObstacleMaterial {
+    Wood,
+    Stone,
+}
+
+impl Obstacle {
+    /// Radius of a circle which holds the whole obstacle
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            Self::Circle { radius } => return *radius,
+            Self::Box { half_size } => return half_size.length(),
+        }
+    }
+
+    /// Distance from the obstacle edge to a point, which is negative inside the obstacle
+    pub fn distance(&self, transform: Transform2D, point: Vec2) -> f32 {
+        return self.contact(transform, point).0;
+    }
+
+    /// Distance from the obstacle edge to a point like `distance` does, and the direction in which
+    /// the point gets out of the obstacle the soonest
+    pub fn contact(&self, transform: Transform2D, point: Vec2) -> (f32, Vec2) {
+        let local = to_local(transform, point);
+
+        let (distance, normal) = match self {
+            Self::Circle { radius } => (
+                local.length() - radius,
+                local.try_normalize().unwrap_or(Vec2::X),
+            ),
+            Self::Box { half_size } => {
+                let q = local.abs() - *half_size;
+
+                if q.x > 0.0 || q.y > 0.0 {
+                    let outside = local - local.clamp(-*half_size, *half_size);
+                    (outside.length(), outside.normalize_or_zero())
+                } else if q.x > q.y {
+                    (q.x, Vec2::new(local.x.signum(), 0.0))
+                } else {
+                    (q.y, Vec2::new(0.0, local.y.signum()))
+                }
+            }
+        };
+
+        return (
+            distance,
+            Vec2::from_angle(transform.rotation).rotate(normal),
+        );
+    }
+
+    /// Whether the obstacle is in the way between two points
+    pub fn blocks(&self, transform: Transform2D, from: Vec2, to: Vec2) -> bool {
+        let closest = transform.position.project_on_clamped(&(from, to));
+
+        if closest.is_far(transform.position, self.bounding_radius()) {
+            return false;
+        }
+
+        return self.intersect(transform, from, to).is_some();
+    }
+
+    /// Where a segment enters the obstacle, as a fraction of the segment length. It's zero if the
+    /// segment starts inside
+    pub fn intersect(&self, transform: Transform2D, from: Vec2, to: Vec2) -> Option<f32> {
+        let from = to_local(transform, from);
+        let direction = to_local(transform, to) - from;
+
+        match self {
+            Self::Circle { radius } => {
+                let c = from.length_squared() - radius * radius;
+
+                if c <= 0.0 {
+                    return Some(0.0);
+                }
+
+                let a = direction.length_squared();
+                let b = from.dot(direction);
+                let discriminant = b * b - a * c;
+
+                if a == 0.0 || discriminant < 0.0 {
+                    return None;
+                }
+
+                let t = (-b - discriminant.sqrt()) / a;
+                return (0.0..=1.0).contains(&t).then_some(t);
+            }
+            Self::Box { half_size } => {
+                let mut t_min = 0.0_f32;
+                let mut t_max = 1.0_f32;
+
+                for (from, direction, half_size) in [
+                    (from.x, direction.x, half_size.x),
+                    (from.y, direction.y, half_size.y),
+                ] {
+                    if direction == 0.0 {
+                        if from.abs() > half_size {
+                            return None;
+                        }
+                    } else {
+                        let t_1 = (-half_size - from) / direction;
+                        let t_2 = (half_size - from) / direction;
+                        t_min = t_min.max(t_1.min(t_2));
+                        t_max = t_max.min(t_1.max(t_2));
+                    }
+                }
+
+                return (t_min <= t_max).then_some(t_min);
+            }
+        }
+    }
+}
+
+/// Places an obstacle. With a color it's drawn as a plain shape, otherwise the map draws it some
+/// other way
+pub struct ObstacleSpawn {
+    pub obstacle: Obstacle,
+    pub material: ObstacleMaterial,
+    pub position: Vec2,
+    pub rotation: f32,
+    pub color: Option<Color>,
+}
+
+impl Command for ObstacleSpawn {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        let transform = Transform {
+            translation: self.position.extend(LAYER_TREE),
+            rotation: Quat::from_rotation_z(self.rotation),
+            ..Transform::default()
+        };
+
+        let entity = world.spawn((transform, self.obstacle, self.material)).id();
+
+        let Some(color) = self.color else {
+            return;
+        };
+
+        let mesh = match self.obstacle {
+            Obstacle::Circle { radius } => Mesh::from(Circle::new(radius)),
+            Obstacle::Box { half_size } => Mesh::from(Rectangle::from_size(half_size * 2.0)),
+        };
+
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(mesh);
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(ColorMaterial::from_color(color));
+
+        world
+            .entity_mut(entity)
+            .insert((Mesh2d(mesh), MeshMaterial2d(material)));
+    }
+}
+
+impl ObstacleMaterial {
+    /// Scales the angle at which projectiles ricochet. Soft materials catch them more often
+    pub const fn hardness(self) -> f32 {
+        match self {
+            Self::Wood => return 0.3,
+            Self::Stone => return 1.0,
+        }
+    }
+}
+
+fn to_local(transform: Transform2D, point: Vec2) -> Vec2 {
+    return Vec2::from_angle(-transform.rotation).rotate(point - transform.position);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::ext::Vec2Ext;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn transform() -> Transform2D {
+        return Transform2D {
+            position: Vec2::new(1.0, 1.0),
+            rotation: FRAC_PI_2,
+        };
+    }
+
+    #[test]
+    fn distance() {
+        let circle = Obstacle::Circle { radius: 0.5 };
+        assert!((circle.distance(transform(), Vec2::new(3.0, 1.0)) - 1.5).abs() < 0.001);
+
+        // the box is turned, so it's long along Y
+        let wall = Obstacle::Box {
+            half_size: Vec2::new(2.0, 0.5),
+        };
+
+        assert!((wall.distance(transform(), Vec2::new(1.0, 2.5)) + 0.5).abs() < 0.001);
+        assert!((wall.distance(transform(), Vec2::new(2.5, 1.0)) - 1.0).abs() < 0.001);
+
+        let (_, normal) = wall.contact(transform(), Vec2::new(1.3, 2.5));
+        assert!(normal.is_close(Vec2::X, 0.001));
+    }
+
+    #[test]
+    fn intersect() {
+        let from = Vec2::new(-1.0, 1.0);
+        let to = Vec2::new(3.0, 1.0);
+
+        let wall = Obstacle::Box {
+            half_size: Vec2::new(2.0, 0.5),
+        };
+
+        let t = wall.intersect(transform(), from, to);
+        assert!(t.is_some_and(|t| (t - 0.375).abs() < 0.001));
+        assert_eq!(
+            wall.intersect(transform(), from, Vec2::new(-1.0, 5.0)),
+            None
+        );
+
+        let circle = Obstacle::Circle { radius: 0.5 };
+        let t = circle.intersect(transform(), from, to);
+        assert!(t.is_some_and(|t| (t - 0.375).abs() < 0.001));
+        assert_eq!(
+            circle.intersect(transform(), Vec2::ONE, Vec2::ZERO),
+            Some(0.0)
+        );
+    }
+}
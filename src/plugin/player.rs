@@ -1,31 +1,46 @@
 use crate::{
-    data::{LAYER_ACTOR_PLAYER, WORLD_SIZE_HALF},
+    data::{LAYER_ACTOR_PLAYER, VIEW_DISTANCE, WORLD_SIZE_HALF},
     plugin::{
-        camera::MainCamera, camera_target::CameraTarget, kinetics::Kinetics, Actor, ActorAction,
-        ActorActions, ActorActionsExt, ActorConfig, ActorSet, Crosshair, Health, StatusBar,
-        WeaponConfig, WeaponSet,
+        bot::{Bot, BotOrder, SquadOrder},
+        camera::MainCamera,
+        camera_target::CameraTarget,
+        kinetics::Kinetics,
+        Actor, ActorAction, ActorActions, ActorActionsExt, ActorConfig, ActorSet, AmmoReserve,
+        Buttons, Crosshair, Health, Inventory, InventoryCommand, InventorySlot, InventoryUse,
+        StatusBar, WeaponConfig, WeaponSet,
     },
-    resource::Settings,
+    resource::{Bindings, Control, ControlsSettings, Settings},
     state::AppState,
     util::ext::{AppExt, QuatExt, Vec2Ext},
 };
 use bevy::{
-    camera::Camera,
+    camera::Projection,
     ecs::{
         component::Component,
         entity::Entity,
         query::{With, Without},
+        resource::Resource,
         schedule::{IntoScheduleConfigs, SystemSet},
-        system::{Command, In, IntoSystem, Query},
+        system::{Command, Commands, Query, ResMut},
     },
-    input::{mouse::MouseMotion, ButtonInput},
-    math::Vec2,
-    prelude::{App, KeyCode, MessageReader, MouseButton, Plugin, Res, Transform, World},
-    transform::components::GlobalTransform,
+    input::{
+        gamepad::Gamepad,
+        mouse::{MouseMotion, MouseWheel},
+        ButtonInput,
+    },
+    math::{Quat, Vec2, Vec3Swizzles},
+    prelude::{
+        App, FixedUpdate, KeyCode, MessageReader, MouseButton, Plugin, Res, Transform, World,
+    },
+    state::condition::in_state,
+    window::{PrimaryWindow, Window},
 };
 
 const EXTRA_ROTATION_MULTIPLAYER: f32 = 0.1;
 const EXTRA_ROTATION_MAX: f32 = 0.11;
+const SPAWN_SPACING: f32 = 1.0;
+/// Allied bots hear orders as far as the player sees
+const ORDER_DISTANCE: f32 = VIEW_DISTANCE;
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlayerSystems;
@@ -34,15 +49,92 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(
+        app.init_resource::<PlayerInput>();
+        app.add_state_system(AppState::Game, on_update_1);
+        // unlike the rest of the simulation, it runs on a network client too, to control its actor
+        app.add_systems(
+            FixedUpdate,
+            on_update_2
+                .in_set(PlayerSystems)
+                .run_if(in_state(AppState::Game)),
+        );
+        app.add_state_system_fixed(
+            AppState::Game,
+            give_orders.in_set(PlayerSystems).after(on_update_2),
+        );
+        app.add_state_system_fixed(
             AppState::Game,
-            (on_update_1.pipe(on_update_2)).in_set(PlayerSystems),
+            use_inventory.in_set(PlayerSystems).after(on_update_2),
         );
     }
 }
 
+/// Input is read from devices every frame, but it's taken by the simulation ticks which may run
+/// several times per frame or not run at all. There is an input for each local player
+#[derive(Default, Resource)]
+pub struct PlayerInput {
+    pending: Vec<Input>,
+    current: Vec<Input>,
+}
+
+impl PlayerInput {
+    /// Input of each player which is going to be taken by the next tick
+    pub fn pending(&self) -> &[Input] {
+        return &self.pending;
+    }
+
+    /// Replaces input of the next tick, for example by a recorded one
+    pub fn replace(&mut self, inputs: &[Input]) {
+        self.pending.clear();
+        self.pending.extend_from_slice(inputs);
+    }
+
+    /// Input of each player taken by the current tick
+    pub fn current(&self) -> &[Input] {
+        return &self.current;
+    }
+
+    /// Replaces input of the current tick, so it can be simulated without devices
+    #[cfg(test)]
+    pub fn replace_current(&mut self, inputs: &[Input]) {
+        self.current.clear();
+        self.current.extend_from_slice(inputs);
+    }
+
+    fn add(&mut self, player: usize, input: Input) {
+        if self.pending.len() <= player {
+            self.pending.resize(player + 1, Input::default());
+        }
+
+        let pending = &mut self.pending[player];
+        let aim_toggle = pending.actions & ActorAction::AimToggle;
+        pending.mouse_motion += input.mouse_motion;
+        pending.zoom += input.zoom;
+        pending.movement = input.movement;
+        pending.actions = input.actions | aim_toggle;
+        pending.viewport = input.viewport;
+        pending.aim = input.aim;
+        pending.order = input.order.or(pending.order);
+        pending.inventory = input.inventory.or(pending.inventory);
+    }
+
+    fn take(&mut self) {
+        self.current.clone_from(&self.pending);
+
+        for pending in &mut self.pending {
+            pending.mouse_motion = Vec2::ZERO;
+            pending.zoom = 0.0;
+            pending.actions.remove(ActorAction::AimToggle);
+            pending.order = None;
+            pending.inventory = None;
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Player {
+    /// The first player uses keyboard and mouse along with a spare gamepad, the rest use gamepads
+    pub index: u8,
     pub is_controllable: bool, // TODO: avoid
     is_aiming: bool,
     extra_rotation: f32,
@@ -63,51 +155,209 @@ fn on_update_1(
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
     mut mouse_motion: MessageReader<MouseMotion>,
-) -> Input {
-    let mut input = Input::default();
+    mut mouse_scroll: MessageReader<MouseWheel>,
+    gamepads: Query<&Gamepad>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut player_input: ResMut<PlayerInput>,
+    settings: Res<Settings>,
+) {
+    let viewport = windows
+        .iter()
+        .next()
+        .map_or(Vec2::ZERO, |w| Vec2::new(w.width(), w.height()));
+
+    let players = usize::from(settings.game.players);
+    let mut gamepads = gamepads.iter();
+
+    // the first player takes a gamepad too, unless the rest of players need them all
+    let gamepad_shared = if gamepads.len() >= players {
+        gamepads.next()
+    } else {
+        None
+    };
 
-    for event in mouse_motion.read() {
-        input.mouse_motion += event.delta;
+    for player in 0..players {
+        let mut input = if player == 0 {
+            let buttons = Buttons {
+                keyboard: Some(&keyboard),
+                mouse: Some(&mouse),
+                wheel: mouse_scroll.read().map(|e| e.y).sum(),
+                gamepad: gamepad_shared,
+            };
+
+            let motion = mouse_motion.read().map(|e| e.delta).sum();
+            read_keyboard_and_mouse(&settings.controls, buttons, motion)
+        } else if let Some(gamepad) = gamepads.next() {
+            read_gamepad(&settings.controls, gamepad)
+        } else {
+            Input::default() // the player idles till a gamepad is connected
+        };
+
+        input.viewport = viewport;
+        player_input.add(player, input);
     }
+}
 
-    if keyboard.pressed(KeyCode::KeyW) {
+/// Reads the keyboard and mouse along with a gamepad, if any, whose sticks take over while tilted
+fn read_keyboard_and_mouse(
+    controls: &ControlsSettings,
+    buttons: Buttons,
+    mouse_motion: Vec2,
+) -> Input {
+    let bindings = &controls.bindings;
+    let mut input = Input {
+        mouse_motion,
+        ..Default::default()
+    };
+
+    if buttons.pressed(bindings.get(Control::MoveForward)) {
         input.movement.x += 1.0;
     }
 
-    if keyboard.pressed(KeyCode::KeyS) {
+    if buttons.pressed(bindings.get(Control::MoveBackward)) {
         input.movement.x -= 1.0;
     }
 
-    if keyboard.pressed(KeyCode::KeyA) {
+    if buttons.pressed(bindings.get(Control::MoveLeft)) {
         input.movement.y += 1.0;
     }
 
-    if keyboard.pressed(KeyCode::KeyD) {
+    if buttons.pressed(bindings.get(Control::MoveRight)) {
         input.movement.y -= 1.0;
     }
 
-    input
-        .actions
-        .set(ActorAction::Sprint, keyboard.pressed(KeyCode::ShiftLeft));
+    read_buttons(bindings, buttons, &mut input);
+
+    if let Some(gamepad) = buttons.gamepad {
+        let (movement, aim) = read_sticks(controls, gamepad);
+
+        if !movement.is_zero() {
+            input.movement = movement;
+        }
+
+        if !movement.is_zero() || !aim.is_zero() {
+            input.aim = Some(aim);
+        }
+    }
+
+    return input;
+}
+
+fn read_gamepad(controls: &ControlsSettings, gamepad: &Gamepad) -> Input {
+    let mut input = Input::default();
+    let (movement, aim) = read_sticks(controls, gamepad);
+
+    input.movement = movement;
+    input.aim = Some(aim);
+
+    let buttons = Buttons {
+        gamepad: Some(gamepad),
+        ..Default::default()
+    };
+
+    read_buttons(&controls.bindings, buttons, &mut input);
+    return input;
+}
+
+/// Returns movement and aim by the sticks
+fn read_sticks(controls: &ControlsSettings, gamepad: &Gamepad) -> (Vec2, Vec2) {
+    let movement = apply_dead_zone(gamepad.left_stick(), controls.gamepad_dead_zone);
+    let aim = apply_dead_zone(gamepad.right_stick(), controls.gamepad_dead_zone);
+    return (
+        Vec2::new(movement.y, -movement.x),
+        aim * controls.gamepad_sensitivity,
+    );
+}
 
-    input
-        .actions
-        .set(ActorAction::Attack, mouse.pressed(MouseButton::Left));
+/// Zeroes a stick tilt within the dead zone and scales the rest, so the tilt still starts from
+/// zero past the dead zone and reaches one at the edge
+fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let tilt = stick.length();
 
-    input
-        .actions
-        .set(ActorAction::Reload, keyboard.pressed(KeyCode::KeyR));
+    if tilt <= dead_zone || dead_zone >= 1.0 {
+        return Vec2::ZERO;
+    }
+
+    let tilt_scaled = f32::min((tilt - dead_zone) / (1.0 - dead_zone), 1.0);
+    return stick * tilt_scaled / tilt;
+}
+
+/// Reads the controls which are same for any devices
+fn read_buttons(bindings: &Bindings, buttons: Buttons, input: &mut Input) {
+    if buttons.just_pressed(bindings.get(Control::ZoomIn)) {
+        input.zoom += 1.0;
+    }
+
+    if buttons.just_pressed(bindings.get(Control::ZoomOut)) {
+        input.zoom -= 1.0;
+    }
+
+    input.actions.set(
+        ActorAction::Sprint,
+        buttons.pressed(bindings.get(Control::Sprint)),
+    );
+
+    input.actions.set(
+        ActorAction::Attack,
+        buttons.pressed(bindings.get(Control::Attack)),
+    );
+
+    input.actions.set(
+        ActorAction::Reload,
+        buttons.pressed(bindings.get(Control::Reload)),
+    );
+
+    input.actions.set(
+        ActorAction::SelectFireMode,
+        buttons.pressed(bindings.get(Control::FireMode)),
+    );
 
     input.actions.set(
         ActorAction::AimToggle,
-        mouse.just_pressed(MouseButton::Right),
+        buttons.just_pressed(bindings.get(Control::Aim)),
     );
 
-    return input;
+    let orders = [
+        (Control::SquadFollow, SquadOrder::Follow),
+        (Control::SquadHold, SquadOrder::Hold),
+        (Control::SquadAttack, SquadOrder::Attack),
+        (Control::SquadRegroup, SquadOrder::Regroup),
+    ];
+
+    for (control, order) in orders {
+        if buttons.just_pressed(bindings.get(control)) {
+            input.order = Some(order);
+        }
+    }
+
+    let inventory = [
+        (
+            Control::WeaponPrimary,
+            InventoryCommand::Select(InventorySlot::Primary),
+        ),
+        (
+            Control::WeaponSecondary,
+            InventoryCommand::Select(InventorySlot::Secondary),
+        ),
+        (
+            Control::WeaponMelee,
+            InventoryCommand::Select(InventorySlot::Melee),
+        ),
+        (Control::WeaponNext, InventoryCommand::Next),
+        (Control::WeaponPrevious, InventoryCommand::Previous),
+        (Control::WeaponDrop, InventoryCommand::Drop),
+        (Control::WeaponPickUp, InventoryCommand::PickUp),
+    ];
+
+    for (control, command) in inventory {
+        if buttons.just_pressed(bindings.get(control)) {
+            input.inventory = Some(command);
+        }
+    }
 }
 
 fn on_update_2(
-    In(input): In<Input>,
+    mut player_input: ResMut<PlayerInput>,
     mut players: Query<
         (
             &mut Player,
@@ -117,9 +367,10 @@ fn on_update_2(
         ),
         Without<MainCamera>,
     >,
-    cameras: Query<(&Camera, &Transform, &GlobalTransform), With<MainCamera>>,
+    cameras: Query<(&Transform, &Projection), With<MainCamera>>,
     settings: Res<Settings>,
 ) {
+    player_input.take();
     let camera = cameras.iter().next();
 
     for (mut player, mut actor, mut transform, camera_target) in players.iter_mut() {
@@ -131,34 +382,49 @@ fn on_update_2(
             continue;
         }
 
+        let input = player_input
+            .current()
+            .get(usize::from(player.index))
+            .copied()
+            .unwrap_or_default();
+
+        let view = camera.and_then(|(camera_transform, projection)| {
+            return View::new(camera_transform, projection, input.viewport);
+        });
+
         actor.movement = input.movement;
         actor.actions = input.actions;
 
         if actor.actions.contains(ActorAction::AimToggle) {
             player.is_aiming = !player.is_aiming;
 
-            if !player.is_aiming {
+            if !player.is_aiming && input.aim.is_none() {
                 // sync player back with camera
-                if let Some(camera) = camera {
-                    transform.rotation = camera.1.rotation.perp();
+                if let Some(view) = &view {
+                    transform.rotation = view.rotation.perp();
                     player.extra_rotation = 0.0;
                 }
             }
         }
 
-        if let Some((camera, _, camera_transform_global)) = camera {
+        if let (Some(view), Some(aim)) = (&view, input.aim) {
+            update_aim_by_stick(&mut actor, &player, view, aim, input.movement);
+        } else if let Some(view) = &view {
+            actor.look_at = None; // the stick could have left it
             update_aim(
                 &mut actor,
                 &mut transform,
                 &mut player,
-                camera,
-                camera_transform_global,
+                view,
                 input.mouse_motion,
                 settings.controls.mouse_sensitivity,
             );
         }
 
-        if player.is_aiming {
+        // a stick aims in the view coordinates, so the camera doesn't turn with such player
+        let is_view_relative = player.is_aiming || input.aim.is_some();
+
+        if is_view_relative {
             // make movement relative to the camera
             actor.movement.y = -actor.movement.y;
             actor.movement = actor.movement.rotate_by_quat(transform.rotation);
@@ -166,15 +432,15 @@ fn on_update_2(
             actor.movement.x = actor.movement.y;
             actor.movement.y = x_copy;
 
-            if let Some(camera) = camera {
-                actor.movement = actor.movement.rotate_by_quat(camera.1.rotation);
+            if let Some(view) = &view {
+                actor.movement = actor.movement.rotate_by_quat(view.rotation);
             }
         } else {
             actor.aim_distance = f32::max(actor.aim_distance, 1.0);
         }
 
         if let Some(mut camera_target) = camera_target {
-            if player.is_aiming {
+            if is_view_relative {
                 camera_target.sync_angle = None;
             } else {
                 camera_target.sync_angle = Some(player.extra_rotation);
@@ -183,12 +449,53 @@ fn on_update_2(
     }
 }
 
+/// Passes squad orders of players by their input
+fn give_orders(
+    player_input: Res<PlayerInput>,
+    players: Query<(Entity, &Player)>,
+    mut commands: Commands,
+) {
+    crate::util::bench::bench!();
+
+    for (leader, player) in players.iter() {
+        let Some(order) = player_input
+            .current()
+            .get(usize::from(player.index))
+            .and_then(|i| i.order)
+        else {
+            continue;
+        };
+
+        commands.queue(SquadOrderGive { leader, order });
+    }
+}
+
+/// Switches and drops weapons of players by their input
+fn use_inventory(
+    player_input: Res<PlayerInput>,
+    players: Query<(Entity, &Player)>,
+    mut commands: Commands,
+) {
+    crate::util::bench::bench!();
+
+    for (entity, player) in players.iter() {
+        let Some(command) = player_input
+            .current()
+            .get(usize::from(player.index))
+            .and_then(|i| i.inventory)
+        else {
+            continue;
+        };
+
+        commands.queue(InventoryUse { entity, command });
+    }
+}
+
 fn update_aim(
     actor: &mut Actor,
     actor_transform: &mut Transform,
     player: &mut Player,
-    camera: &Camera,
-    camera_transform: &GlobalTransform,
+    view: &View,
     mouse_motion: Vec2,
     mouse_sensitivity: f32,
 ) {
@@ -208,27 +515,15 @@ fn update_aim(
     // weapon recoil, so aim should be affected too
     let position = actor_transform.translation.truncate();
     let on_world_old = position + actor_transform.rotation.as_vec() * actor.aim_distance;
-
-    let Ok(on_screen_old) = camera.world_to_viewport(camera_transform, on_world_old.extend(0.0))
-    else {
-        return;
-    };
-
+    let on_screen_old = view.world_to_viewport(on_world_old);
     let mut on_screen_new = on_screen_old + mouse_motion;
 
     // clamp aim inside view port
-    if let Some(viewport_size) = camera.logical_viewport_size() {
-        on_screen_new.x = on_screen_new.x.clamp(0.0, viewport_size.x);
-        on_screen_new.y = on_screen_new.y.clamp(0.0, viewport_size.y);
-    }
+    on_screen_new.x = on_screen_new.x.clamp(0.0, view.size.x);
+    on_screen_new.y = on_screen_new.y.clamp(0.0, view.size.y);
 
     // put aim to it's updated position
-    let Ok(on_world_new) = camera
-        .viewport_to_world(camera_transform, on_screen_new)
-        .map(|v| v.origin.truncate())
-    else {
-        return;
-    };
+    let on_world_new = view.viewport_to_world(on_screen_new);
 
     // update only when cursor moved more than 1px actually, otherwise errors may grow
     if (on_screen_new - on_screen_old).is_long(0.99) {
@@ -240,8 +535,65 @@ fn update_aim(
     }
 }
 
+/// Twin-stick aiming: the actor turns to where the stick points on the screen, and the tilt sets
+/// how far it aims. With the stick released, the actor faces where it walks unless aiming is on
+fn update_aim_by_stick(actor: &mut Actor, player: &Player, view: &View, aim: Vec2, movement: Vec2) {
+    if !aim.is_zero() {
+        let reach = view.size.min_element() / 2.0 * view.scale;
+        actor.look_at = Some(view.direction_to_world(aim).as_quat().angle_z());
+        actor.aim_distance = f32::max(aim.length() * reach, 1.0);
+    } else if !player.is_aiming && !movement.is_zero() {
+        let direction = Vec2::new(-movement.y, movement.x);
+        actor.look_at = Some(view.direction_to_world(direction).as_quat().angle_z());
+        actor.aim_distance = 1.0;
+    } else {
+        actor.look_at = None;
+    }
+}
+
+/// Passes a squad order to allied bots around the leader, which makes them follow it
+pub struct SquadOrderGive {
+    pub leader: Entity,
+    pub order: SquadOrder,
+}
+
+impl Command for SquadOrderGive {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        let (Some(leader_actor), Some(leader_transform)) = (
+            world.get::<Actor>(self.leader),
+            world.get::<Transform>(self.leader),
+        ) else {
+            return;
+        };
+
+        let kind = leader_actor.config.kind;
+        let position = leader_transform.translation.xy();
+        let crosshair = position + leader_transform.rotation.as_vec() * leader_actor.aim_distance;
+        let mut bots = world.query::<(&mut Bot, &Actor, &Transform)>();
+
+        for (mut bot, actor, transform) in bots.iter_mut(world) {
+            let bot_position = transform.translation.xy();
+
+            if actor.config.kind != kind || bot_position.is_far(position, ORDER_DISTANCE) {
+                continue;
+            }
+
+            bot.leader = Some(self.leader);
+            bot.order = Some(match self.order {
+                SquadOrder::Follow => BotOrder::Follow,
+                SquadOrder::Hold => BotOrder::Hold(bot_position),
+                SquadOrder::Attack => BotOrder::Attack(crosshair),
+                SquadOrder::Regroup => BotOrder::Regroup,
+            });
+        }
+    }
+}
+
 pub struct PlayerSet {
     pub entity: Entity,
+    pub index: u8,
     pub is_controllable: bool,
 }
 
@@ -272,53 +624,183 @@ impl Command for PlayerSet {
         world
             .entity_mut(self.entity)
             .insert(Player {
+                index: self.index,
                 is_controllable: self.is_controllable,
                 is_aiming: false,
                 extra_rotation: 0.0,
             })
-            .insert(CameraTarget::default());
+            .insert(CameraTarget::default())
+            .insert(Inventory::default())
+            .insert(AmmoReserve::default());
 
         StatusBar::spawn(world, self.entity);
     }
 }
 
 pub struct PlayerSpawn {
-    pub config: &'static ActorConfig,
-    pub weapon: &'static WeaponConfig,
+    pub config: Option<&'static ActorConfig>,
+    pub weapon: Option<&'static WeaponConfig>,
+    pub index: u8,
     pub is_controllable: bool,
 }
 
-impl Command for PlayerSpawn {
-    type Out = ();
+impl PlayerSpawn {
+    /// Returns the player entity, unless there was no actor config to spawn
+    pub fn spawn(self, world: &mut World) -> Option<Entity> {
+        let Some(config) = self.config else {
+            log::warn!("Can't spawn player. No actor config specified");
+            return None;
+        };
 
-    fn apply(self, world: &mut World) {
         let entity = world.spawn_empty().id();
 
         ActorSet {
             entity,
-            config: self.config,
-            position: Vec2::ZERO,
+            config,
+            position: Vec2::new(0.0, -f32::from(self.index) * SPAWN_SPACING),
             rotation: 0.0,
         }
         .apply(world);
 
         PlayerSet {
             entity,
+            index: self.index,
             is_controllable: self.is_controllable,
         }
         .apply(world);
 
         WeaponSet {
             entity,
-            weapon: Some(self.weapon),
+            weapon: self.weapon,
         }
         .apply(world);
+
+        if let (Some(weapon), Some(mut reserve)) =
+            (self.weapon, world.get_mut::<AmmoReserve>(entity))
+        {
+            reserve.add_magazines(weapon, AmmoReserve::MAGAZINES_WITH_WEAPON);
+        }
+
+        return Some(entity);
     }
 }
 
-#[derive(Default)]
-struct Input {
-    mouse_motion: Vec2,
-    movement: Vec2,
-    actions: ActorActions,
+impl Command for PlayerSpawn {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        self.spawn(world);
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Input {
+    pub mouse_motion: Vec2,
+    pub movement: Vec2,
+    pub actions: ActorActions,
+    pub zoom: f32,
+    /// Logical size of the window, which affects aiming
+    pub viewport: Vec2,
+    /// Stick aim of a gamepad player, scaled by the sensitivity. It's none with a mouse
+    pub aim: Option<Vec2>,
+    /// A squad order given on this tick
+    pub order: Option<SquadOrder>,
+    /// An inventory command given on this tick
+    pub inventory: Option<InventoryCommand>,
+}
+
+/// Same as the camera's conversions between world and viewport, but it relies only on the state
+/// updated by the simulation, while the camera matrices are updated once per frame
+struct View {
+    position: Vec2,
+    rotation: Quat,
+    scale: f32,
+    size: Vec2,
+}
+
+impl View {
+    fn new(transform: &Transform, projection: &Projection, size: Vec2) -> Option<Self> {
+        let Projection::Orthographic(projection) = projection else {
+            return None;
+        };
+
+        if size.x <= 0.0 || size.y <= 0.0 {
+            return None;
+        }
+
+        return Some(Self {
+            position: transform.translation.xy(),
+            rotation: transform.rotation,
+            scale: projection.scale,
+            size,
+        });
+    }
+
+    fn world_to_viewport(&self, point: Vec2) -> Vec2 {
+        let local =
+            (self.rotation.inverse() * (point - self.position).extend(0.0)).xy() / self.scale;
+        return Vec2::new(self.size.x / 2.0 + local.x, self.size.y / 2.0 - local.y);
+    }
+
+    fn viewport_to_world(&self, point: Vec2) -> Vec2 {
+        let local =
+            Vec2::new(point.x - self.size.x / 2.0, self.size.y / 2.0 - point.y) * self.scale;
+        return self.position + (self.rotation * local.extend(0.0)).xy();
+    }
+
+    /// Turns a direction on the screen, where Y goes up, to the world one
+    fn direction_to_world(&self, direction: Vec2) -> Vec2 {
+        return (self.rotation * direction.extend(0.0)).xy();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::input::gamepad::{GamepadAxis, GamepadButton};
+
+    #[test]
+    fn gamepad_of_single_player() {
+        let controls = ControlsSettings::default();
+        let keyboard = ButtonInput::<KeyCode>::default();
+        let mouse = ButtonInput::<MouseButton>::default();
+        let mut gamepad = Gamepad::default();
+
+        let read = |gamepad: &Gamepad, mouse_motion: Vec2| {
+            let buttons = Buttons {
+                keyboard: Some(&keyboard),
+                mouse: Some(&mouse),
+                wheel: 0.0,
+                gamepad: Some(gamepad),
+            };
+
+            return read_keyboard_and_mouse(&controls, buttons, mouse_motion);
+        };
+
+        // released sticks leave aiming to the mouse
+        let input = read(&gamepad, Vec2::new(1.0, 0.0));
+        assert_eq!(input.aim, None);
+        assert_eq!(input.movement, Vec2::ZERO);
+
+        gamepad.analog_mut().set(GamepadAxis::LeftStickY, 1.0);
+        gamepad.analog_mut().set(GamepadAxis::RightStickX, 1.0);
+        gamepad.digital_mut().press(GamepadButton::RightTrigger2);
+
+        let input = read(&gamepad, Vec2::ZERO);
+        assert_eq!(input.movement, Vec2::new(1.0, 0.0));
+        assert_eq!(input.aim, Some(Vec2::X * controls.gamepad_sensitivity));
+        assert!(input.actions.contains(ActorAction::Attack));
+    }
+
+    #[test]
+    fn dead_zone() {
+        assert_eq!(apply_dead_zone(Vec2::new(0.1, 0.1), 0.2), Vec2::ZERO);
+        assert_eq!(
+            apply_dead_zone(Vec2::new(0.0, -1.0), 0.2),
+            Vec2::new(0.0, -1.0)
+        );
+        assert!(apply_dead_zone(Vec2::new(0.6, 0.0), 0.2).is_close(Vec2::new(0.5, 0.0), 0.001));
+        assert!((apply_dead_zone(Vec2::new(1.0, 1.0), 0.2).length() - 1.0).abs() < 0.001);
+        assert_eq!(apply_dead_zone(Vec2::X, 1.0), Vec2::ZERO);
+    }
 }
@@ -13,6 +13,8 @@ pub struct Projectile {
     pub initial_velocity: Vec2,
     pub distance_limit: f32,
     pub shooter: Option<Entity>,
+    /// Bodies which it has passed through already, so they aren't hit twice
+    pub pierced: Vec<Entity>,
     pub stopped: bool,
 }
 
@@ -32,7 +34,18 @@ impl Projectile {
             initial_velocity: velocity,
             distance_limit: distance_limit.clamp(0.0, config.physics.distance_limit()),
             shooter,
+            pierced: Vec::new(),
             stopped: false,
         };
     }
+
+    /// Continues the flight from the given position with a new velocity, like after piercing a
+    /// body or a ricochet. The distance limit is shortened by the distance traveled so far
+    pub fn redirect(&mut self, time: Duration, position: Vec2, velocity: Vec2) {
+        let traveled = self.initial_position.distance(position);
+        self.distance_limit = f32::max(self.distance_limit - traveled, 0.0);
+        self.initial_time = time;
+        self.initial_position = position;
+        self.initial_velocity = velocity;
+    }
 }
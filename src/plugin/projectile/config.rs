@@ -1,11 +1,20 @@
+use serde::{de::Error, Deserialize, Deserializer};
 use std::time::Duration;
 
 #[derive(Clone)]
 pub struct ProjectileConfig {
+    pub name: &'static str,
     pub fragments: u8,
     pub mass: f32,
     pub size: f32,
     pub physics: ProjectilePhysics,
+    /// Share of the energy kept after piercing a body. Zero means the body stops it
+    pub penetration: f32,
+    /// The largest angle between the flight and a surface at which it ricochets off the hardest
+    /// material. Zero means it never does
+    pub ricochet_angle: f32,
+    /// Share of the velocity kept after a ricochet
+    pub ricochet_velocity: f32,
     pub explosion: Option<ProjectileExplosion>,
 }
 
@@ -21,57 +30,103 @@ impl ProjectileConfig {
     pub const ROCKET_ACCELERATION_FACTOR: f32 = 8.0;
     pub const ROCKET_ACCELERATION_TIME: Duration = Duration::from_millis(800);
 
+    pub const ALL: &'static [Self] = &[
+        Self::_9X18,
+        Self::_7_62X25,
+        Self::_12X76,
+        Self::_5_45X39,
+        Self::_7_62X54,
+        Self::TBG_7V,
+    ];
+
     pub const _9X18: Self = Self {
+        name: "9x18",
         fragments: 1,
         mass: 0.0061,
         size: 0.7,
         physics: ProjectilePhysics::Bullet,
+        penetration: 0.2,
+        ricochet_angle: 0.35,
+        ricochet_velocity: 0.6,
         explosion: None,
     };
 
     pub const _7_62X25: Self = Self {
+        name: "7.62x25",
         fragments: 1,
         mass: 0.0055,
         size: 0.7,
         physics: ProjectilePhysics::Bullet,
+        penetration: 0.35,
+        ricochet_angle: 0.3,
+        ricochet_velocity: 0.6,
         explosion: None,
     };
 
     pub const _12X76: Self = Self {
+        name: "12x76",
         fragments: 12,
         mass: 0.048,
         size: 0.1,
         physics: ProjectilePhysics::Bullet,
+        penetration: 0.0,
+        ricochet_angle: 0.25,
+        ricochet_velocity: 0.5,
         explosion: None,
     };
 
     pub const _5_45X39: Self = Self {
+        name: "5.45x39",
         fragments: 1,
         mass: 0.0034,
         size: 1.0,
         physics: ProjectilePhysics::Bullet,
+        penetration: 0.45,
+        ricochet_angle: 0.35,
+        ricochet_velocity: 0.6,
         explosion: None,
     };
 
     pub const _7_62X54: Self = Self {
+        name: "7.62x54",
         fragments: 1,
         mass: 0.0096,
         size: 1.2,
         physics: ProjectilePhysics::Bullet,
+        penetration: 0.65,
+        ricochet_angle: 0.25,
+        ricochet_velocity: 0.7,
         explosion: None,
     };
 
     pub const TBG_7V: Self = Self {
+        name: "TBG-7V",
         fragments: 1,
         mass: 4.3,
         size: 5.0,
         physics: ProjectilePhysics::Rocket,
+        penetration: 0.0,
+        ricochet_angle: 0.0,
+        ricochet_velocity: 0.0,
         explosion: Some(ProjectileExplosion {
             radius: 4.0,
             energy: 8.0,
         }),
     };
 
+    pub fn find(name: &str) -> Option<&'static Self> {
+        return Self::ALL.iter().find(|p| p.name == name);
+    }
+
+    /// Allows data files to refer projectiles by name
+    pub fn deserialize_by_name<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<&'static Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        return Self::find(&name)
+            .ok_or_else(|| D::Error::custom(format!("unknown projectile `{}`", name)));
+    }
+
     pub const fn acceleration(&self) -> f32 {
         return -1.0 / self.fragment_mass() * 0.006 - 4.2;
     }
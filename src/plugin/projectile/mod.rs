@@ -19,7 +19,7 @@ pub struct ProjectilePlugin;
 impl Plugin for ProjectilePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<ProjectileMaterial>::default());
-        app.add_state_system(
+        app.add_state_system_fixed(
             AppState::Game,
             sys_update::on_update.after(CollisionSystems),
         );
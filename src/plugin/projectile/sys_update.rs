@@ -1,10 +1,11 @@
 use crate::{
+    data::DISTANCE_1MM,
     plugin::{
-        collision::Collision, projectile::state::ProjectileState, Actor, AudioPlay, AudioTracker,
-        Explode, Projectile,
+        collision::Collision, projectile::state::ProjectileState, scenario::FriendlyFire, Actor,
+        AudioPlay, AudioTracker, Explode, Obstacle, ObstacleMaterial, Projectile, ProjectileConfig,
     },
     resource::HitResource,
-    util::{ext::Vec2Ext, geometry::GeometryProjection, math::angle_factor_signed},
+    util::{ext::Vec2Ext, geometry::GeometryProjection, math::angle_factor_signed, Transform2D},
 };
 use bevy::{
     ecs::{
@@ -21,10 +22,12 @@ const SPIN_FACTOR: f32 = 1.5;
 
 pub fn on_update(
     mut projectiles: Query<(Entity, &mut Projectile, &mut Transform)>,
-    obstacles: Query<(Entity, &Collision, &Transform, &Actor), Without<Projectile>>,
+    bodies: Query<(Entity, &Collision, &Transform, &Actor), Without<Projectile>>,
+    obstacles: Query<(&Transform, &Obstacle, &ObstacleMaterial), Without<Projectile>>,
     mut hits: Deferred<HitResource>,
     mut commands: Commands,
     audio: Res<AudioTracker>,
+    friendly_fire: Res<FriendlyFire>,
     time: Res<Time>,
 ) {
     crate::util::bench::bench!();
@@ -53,27 +56,65 @@ pub fn on_update(
         let mut s0 = ProjectileState::calc(&j, t0);
         let mut p0 = s0.position();
         let mut stopped = s0.stopped();
+        let mut redirect = None;
+        let mut pierced = None;
 
         if !stopped {
             let p1 = ProjectileState::calc(&j, t1).position();
+            let cover = Cover::find(p0, p1, &obstacles);
 
-            if let Some(victim) = Victim::find(p0, p1, &obstacles, j.shooter) {
-                stopped = true;
+            // a body behind the cover is safe
+            let victim = Victim::find(p0, p1, &bodies, &j, *friendly_fire).filter(|v| {
+                return cover.as_ref().is_none_or(|c| {
+                    return v.contact.distance_squared(p1) < c.contact.distance_squared(p1);
+                });
+            });
 
+            if let Some(victim) = victim {
                 // set projectile state at contact position. it will update velocity at that time too
                 s0.update_by_traveled_distance(victim.contact - j.initial_position);
                 p0 = s0.position(); // we updated state, so update position too
 
                 // do not do double damage with explosives, since they dame by explosion not the hit
                 if j.config.explosion.is_none() {
-                    victim.hit(&s0, &audio, &mut hits);
+                    let factor = if victim.is_ally {
+                        log::info!(
+                            "{:?} hit an ally {} by friendly fire",
+                            j.shooter,
+                            victim.entity
+                        );
+                        friendly_fire.damage_factor()
+                    } else {
+                        1.0
+                    };
+
+                    victim.hit(&s0, factor, &audio, &mut hits);
                 }
+
+                redirect = pierce(&s0).map(|v| (p0, v));
+                pierced = Some(victim.entity);
+                stopped = redirect.is_none();
+            } else if let Some(cover) = cover {
+                s0.update_by_traveled_distance(cover.contact - j.initial_position);
+                p0 = s0.position();
+
+                // it starts a bit off the surface, otherwise it would hit it again at once
+                redirect = cover
+                    .ricochet(&s0)
+                    .map(|v| (p0 + cover.normal * DISTANCE_1MM, v));
+                stopped = redirect.is_none();
             }
         }
 
         let p2 = ProjectileState::calc(&j, t2).position();
         update_transform(&j, p0, p2, &mut transform);
 
+        if let Some((position, velocity)) = redirect {
+            // the flight goes on from the current tick, so the next one checks the rest of the way
+            j.redirect(t0, position, velocity);
+            j.pierced.extend(pierced);
+        }
+
         if stopped {
             j.stopped = true; // only stop (destroy) on next frame so player have time to see projectile hits the target
 
@@ -93,23 +134,32 @@ struct Victim {
     position: Vec2,
     contact: Vec2,
     distance_from_projectile_head: f32,
+    /// Of the same kind as the shooter
+    is_ally: bool,
 }
 
 impl Victim {
     fn find(
         head: Vec2,
         tail: Vec2,
-        obstacles: &Query<(Entity, &Collision, &Transform, &Actor), Without<Projectile>>,
-        shooter: Option<Entity>,
+        bodies: &Query<(Entity, &Collision, &Transform, &Actor), Without<Projectile>>,
+        projectile: &Projectile,
+        friendly_fire: FriendlyFire,
     ) -> Option<Self> {
         let mut victim: Option<Victim> = None;
 
-        let shooter_kind = shooter
-            .and_then(|e| obstacles.get(e).ok())
+        let shooter_kind = projectile
+            .shooter
+            .and_then(|e| bodies.get(e).ok())
             .map(|q| q.3.config.kind);
 
-        for (entity, collision, transform, actor) in obstacles.iter() {
-            if shooter == Some(entity) || shooter_kind == Some(actor.config.kind) {
+        for (entity, collision, transform, actor) in bodies.iter() {
+            let is_ally = shooter_kind == Some(actor.config.kind);
+
+            if projectile.shooter == Some(entity)
+                || (is_ally && !friendly_fire.is_on())
+                || projectile.pierced.contains(&entity)
+            {
                 continue;
             }
 
@@ -137,6 +187,7 @@ impl Victim {
                         position: obstacle,
                         contact,
                         distance_from_projectile_head: head_distance,
+                        is_ally,
                     });
                 }
             }
@@ -145,8 +196,9 @@ impl Victim {
         return victim;
     }
 
-    fn hit(&self, s: &ProjectileState, audio: &AudioTracker, hits: &mut HitResource) {
-        let force = s.velocity() * s.projectile.config.fragment_mass();
+    /// The factor scales the damage, like for friendly fire
+    fn hit(&self, s: &ProjectileState, factor: f32, audio: &AudioTracker, hits: &mut HitResource) {
+        let force = s.velocity() * s.projectile.config.fragment_mass() * factor;
 
         if force.is_zero() {
             return;
@@ -168,6 +220,92 @@ impl Victim {
     }
 }
 
+/// Velocity after piercing a body, unless the body stops the projectile
+fn pierce(s: &ProjectileState) -> Option<Vec2> {
+    let config = s.projectile.config;
+
+    if config.explosion.is_some() {
+        return None;
+    }
+
+    // the energy goes with the squared velocity
+    let velocity = s.velocity() * config.penetration.sqrt();
+    return velocity
+        .is_long(ProjectileConfig::VELOCITY_MIN)
+        .then_some(velocity);
+}
+
+/// An obstacle on the way of a projectile
+struct Cover {
+    contact: Vec2,
+    normal: Vec2,
+    material: ObstacleMaterial,
+}
+
+impl Cover {
+    /// The first obstacle which the projectile meets, moving from its tail to its head
+    fn find(
+        head: Vec2,
+        tail: Vec2,
+        obstacles: &Query<(&Transform, &Obstacle, &ObstacleMaterial), Without<Projectile>>,
+    ) -> Option<Self> {
+        let mut cover: Option<(f32, Cover)> = None;
+
+        for (transform, obstacle, material) in obstacles.iter() {
+            let position = transform.translation.xy();
+            let closest = position.project_on_clamped(&(head, tail));
+
+            if closest.is_far(position, obstacle.bounding_radius()) {
+                continue;
+            }
+
+            let transform = Transform2D::from(transform);
+
+            let Some(t) = obstacle.intersect(transform, tail, head) else {
+                continue;
+            };
+
+            if cover.as_ref().is_none_or(|c| t < c.0) {
+                let contact = tail.lerp(head, t);
+
+                cover = Some((
+                    t,
+                    Cover {
+                        contact,
+                        normal: obstacle.contact(transform, contact).1,
+                        material: *material,
+                    },
+                ));
+            }
+        }
+
+        return cover.map(|c| c.1);
+    }
+
+    /// Velocity after bouncing off, unless the projectile comes at too steep angle
+    fn ricochet(&self, s: &ProjectileState) -> Option<Vec2> {
+        let config = s.projectile.config;
+
+        if config.explosion.is_some() {
+            return None;
+        }
+
+        let velocity = s.velocity();
+        let angle = f32::asin((-velocity.normalize_or_zero().dot(self.normal)).clamp(-1.0, 1.0));
+
+        if angle > config.ricochet_angle * self.material.hardness() {
+            return None;
+        }
+
+        let reflected = velocity - 2.0 * velocity.dot(self.normal) * self.normal;
+        let velocity = reflected * config.ricochet_velocity;
+
+        return velocity
+            .is_long(ProjectileConfig::VELOCITY_MIN)
+            .then_some(velocity);
+    }
+}
+
 fn update_transform(projectile: &Projectile, head: Vec2, tail: Vec2, transform: &mut Transform) {
     let center = (head + tail) / 2.0;
     transform.translation.x = center.x;
@@ -175,3 +313,39 @@ fn update_transform(projectile: &Projectile, head: Vec2, tail: Vec2, transform:
     transform.scale.x = (head - tail).length();
     transform.scale.y = projectile.config.size;
 }
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::util::test::assert_vec_is_close;
+
+    fn ricochet(velocity: Vec2, material: ObstacleMaterial) -> Option<Vec2> {
+        let j = Projectile::new(
+            &ProjectileConfig::_5_45X39,
+            Duration::ZERO,
+            Vec2::ZERO,
+            velocity,
+            f32::INFINITY,
+            None,
+        );
+
+        let cover = Cover {
+            contact: Vec2::ZERO,
+            normal: Vec2::Y,
+            material,
+        };
+
+        return cover.ricochet(&ProjectileState::calc(&j, Duration::ZERO));
+    }
+
+    #[test]
+    fn ricochet_by_angle() {
+        let grazing = Vec2::new(400.0, -60.0);
+        let velocity = ricochet(grazing, ObstacleMaterial::Stone).unwrap();
+
+        assert_vec_is_close!(velocity, Vec2::new(240.0, 36.0), 0.01);
+        assert!(ricochet(grazing, ObstacleMaterial::Wood).is_none());
+        assert!(ricochet(Vec2::new(400.0, -400.0), ObstacleMaterial::Stone).is_none());
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    plugin::{bot::SquadOrder, player::Input, ActorActions, InventoryCommand},
+    resource::Settings,
+    util::ByteReader,
+};
+use anyhow::{bail, ensure, Context, Result};
+use bevy::math::Vec2;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8; 8] = b"SHOOTREP";
+const VERSION: u16 = 6;
+
+const TAG_INPUT: u8 = 0;
+const TAG_CHECKSUM: u8 = 1;
+
+/// A recorded game session: everything needed to play it out again
+#[derive(Clone)]
+pub struct Replay {
+    pub seed: u64,
+    pub settings: Settings,
+    /// Input of every player by tick. Same input of consequent ticks is stored once with a repeat
+    /// count
+    pub inputs: Vec<(u16, Vec<Input>)>,
+    /// Hashes of the world state by tick, to find out where the replay diverges from the record
+    pub checksums: Vec<(u32, u64)>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self> {
+        let context = || format!("Failed to load replay {}", path.display());
+        let data = std::fs::read(path).with_context(context)?;
+        return Self::decode(&data).with_context(context);
+    }
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = ByteReader::new(data);
+
+        ensure!(reader.take::<8>()? == *MAGIC, "Not a replay file");
+
+        let version = u16::from_le_bytes(reader.take()?);
+        ensure!(
+            version == VERSION,
+            "Unsupported replay version {}, expected {}",
+            version,
+            VERSION,
+        );
+
+        let seed = u64::from_le_bytes(reader.take()?);
+        let settings_length = u32::from_le_bytes(reader.take()?) as usize;
+        let settings = std::str::from_utf8(reader.take_slice(settings_length)?)?;
+        let settings = toml::from_str(settings).context("Invalid settings")?;
+
+        let mut inputs = Vec::new();
+        let mut checksums = Vec::new();
+
+        while !reader.is_empty() {
+            match reader.take_u8()? {
+                TAG_INPUT => {
+                    let count = u16::from_le_bytes(reader.take()?);
+                    let players = reader.take_u8()?;
+                    let mut input = Vec::with_capacity(usize::from(players));
+
+                    for _ in 0..players {
+                        input.push(decode_input(&mut reader)?);
+                    }
+
+                    inputs.push((count, input));
+                }
+                TAG_CHECKSUM => {
+                    let tick = u32::from_le_bytes(reader.take()?);
+                    let checksum = u64::from_le_bytes(reader.take()?);
+                    checksums.push((tick, checksum));
+                }
+                tag => {
+                    bail!("Unknown record {}", tag);
+                }
+            }
+        }
+
+        return Ok(Self {
+            seed,
+            settings,
+            inputs,
+            checksums,
+        });
+    }
+}
+
+/// Writes a replay while the game goes. Input of consequent ticks is buffered until it changes
+pub struct ReplayWriter {
+    output: BufWriter<File>,
+    input: Option<(u16, Vec<Input>)>,
+}
+
+impl ReplayWriter {
+    pub fn create(path: &Path, seed: u64, settings: &Settings) -> Result<Self> {
+        let context = || format!("Failed to create replay {}", path.display());
+        let file = File::create(path).with_context(context)?;
+        let mut writer = Self {
+            output: BufWriter::new(file),
+            input: None,
+        };
+
+        let settings = toml::to_string(settings).with_context(context)?;
+        let settings_length = u32::try_from(settings.len()).with_context(context)?;
+
+        let output = &mut writer.output;
+        output.write_all(MAGIC).with_context(context)?;
+        output
+            .write_all(&VERSION.to_le_bytes())
+            .with_context(context)?;
+        output
+            .write_all(&seed.to_le_bytes())
+            .with_context(context)?;
+        output
+            .write_all(&settings_length.to_le_bytes())
+            .with_context(context)?;
+        output
+            .write_all(settings.as_bytes())
+            .with_context(context)?;
+
+        return Ok(writer);
+    }
+
+    pub fn add_input(&mut self, input: &[Input]) -> Result<()> {
+        if let Some((count, previous)) = &mut self.input {
+            if previous == input && *count < u16::MAX {
+                *count += 1;
+                return Ok(());
+            }
+        }
+
+        self.write_input()?;
+        self.input = Some((1, input.to_vec()));
+        return Ok(());
+    }
+
+    pub fn add_checksum(&mut self, tick: u32, checksum: u64) -> Result<()> {
+        self.write_input()?;
+        self.output.write_all(&[TAG_CHECKSUM])?;
+        self.output.write_all(&tick.to_le_bytes())?;
+        self.output.write_all(&checksum.to_le_bytes())?;
+        self.output.flush()?;
+        return Ok(());
+    }
+
+    fn write_input(&mut self) -> Result<()> {
+        if let Some((count, input)) = self.input.take() {
+            self.output.write_all(&[TAG_INPUT])?;
+            self.output.write_all(&count.to_le_bytes())?;
+            self.output.write_all(&[u8::try_from(input.len())?])?;
+
+            for input in &input {
+                encode_input(&mut self.output, input)?;
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+impl Drop for ReplayWriter {
+    fn drop(&mut self) {
+        if let Err(error) = self.write_input().and_then(|_| Ok(self.output.flush()?)) {
+            log::error!("Failed to finish replay: {:?}", error);
+        }
+    }
+}
+
+fn encode_input(output: &mut impl Write, input: &Input) -> Result<()> {
+    for value in [input.movement.x, input.movement.y] {
+        output.write_all(&value.to_le_bytes())?;
+    }
+
+    output.write_all(&[input.actions.as_u8_truncated()])?;
+
+    for value in [input.mouse_motion.x, input.mouse_motion.y, input.zoom] {
+        output.write_all(&value.to_le_bytes())?;
+    }
+
+    for value in [input.viewport.x, input.viewport.y] {
+        output.write_all(&value.to_le_bytes())?;
+    }
+
+    if let Some(aim) = input.aim {
+        output.write_all(&[1])?;
+
+        for value in [aim.x, aim.y] {
+            output.write_all(&value.to_le_bytes())?;
+        }
+    } else {
+        output.write_all(&[0])?;
+    }
+
+    // zero is no order
+    output.write_all(&[input.order.map_or(0, |o| o as u8 + 1)])?;
+
+    // zero is no inventory command
+    let inventory = input
+        .inventory
+        .and_then(|c| InventoryCommand::ALL.iter().position(|a| *a == c))
+        .map_or(0, |i| i as u8 + 1);
+
+    output.write_all(&[inventory])?;
+
+    return Ok(());
+}
+
+fn decode_input(reader: &mut ByteReader) -> Result<Input> {
+    let movement = Vec2::new(reader.take_f32()?, reader.take_f32()?);
+
+    let Some(actions) = ActorActions::try_from_u8(reader.take_u8()?) else {
+        bail!("Unknown actions");
+    };
+
+    return Ok(Input {
+        movement,
+        actions,
+        mouse_motion: Vec2::new(reader.take_f32()?, reader.take_f32()?),
+        zoom: reader.take_f32()?,
+        viewport: Vec2::new(reader.take_f32()?, reader.take_f32()?),
+        aim: match reader.take_u8()? {
+            0 => None,
+            _ => Some(Vec2::new(reader.take_f32()?, reader.take_f32()?)),
+        },
+        order: match reader.take_u8()? {
+            0 => None,
+            n => Some(
+                *SquadOrder::ALL
+                    .get(usize::from(n - 1))
+                    .context("Unknown squad order")?,
+            ),
+        },
+        inventory: match reader.take_u8()? {
+            0 => None,
+            n => Some(
+                *InventoryCommand::ALL
+                    .get(usize::from(n - 1))
+                    .context("Unknown inventory command")?,
+            ),
+        },
+    });
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::plugin::ActorAction;
+
+    #[test]
+    fn write_and_load() {
+        let path = std::env::temp_dir().join("shooter_write_and_load.replay");
+        let input_1 = Input::default();
+        let input_2 = Input {
+            movement: Vec2::new(1.0, -1.0),
+            actions: ActorAction::Attack | ActorAction::SelectFireMode,
+            mouse_motion: Vec2::new(-3.5, 2.0),
+            zoom: 1.0,
+            viewport: Vec2::new(800.0, 600.0),
+            aim: Some(Vec2::new(0.5, -0.25)),
+            order: Some(SquadOrder::Regroup),
+            inventory: Some(InventoryCommand::Drop),
+        };
+
+        {
+            let mut writer = ReplayWriter::create(&path, 42, &Settings::default()).unwrap();
+
+            for input in [[input_1], [input_1], [input_1], [input_2], [input_1]] {
+                assert!(writer.add_input(&input).is_ok());
+            }
+
+            assert!(writer.add_checksum(4, 7).is_ok());
+            assert!(writer.add_input(&[input_1]).is_ok());
+            assert!(writer.add_input(&[input_1, input_2]).is_ok());
+        }
+
+        let replay = Replay::load(&path).unwrap();
+
+        assert_eq!(replay.seed, 42);
+        assert_eq!(
+            replay.inputs,
+            vec![
+                (3, vec![input_1]),
+                (1, vec![input_2]),
+                (1, vec![input_1]),
+                (1, vec![input_1]),
+                (1, vec![input_1, input_2]),
+            ],
+        );
+        assert_eq!(replay.checksums, vec![(4, 7)]);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&(VERSION + 1).to_le_bytes());
+
+        let error = format!("{:#}", Replay::decode(&data).err().unwrap());
+        let expected = format!(
+            "Unsupported replay version {}, expected {}",
+            VERSION + 1,
+            VERSION,
+        );
+
+        assert_eq!(error, expected);
+    }
+}
@@ -0,0 +1,257 @@
+mod file;
+
+pub use self::file::*;
+use crate::{
+    plugin::{
+        player::{Input, PlayerInput, PlayerSystems},
+        Actor, Buttons, Health, Notify,
+    },
+    resource::{Control, RngResource, Settings},
+    state::AppState,
+    util::ext::AppExt,
+};
+use bevy::{
+    app::{App, FixedLast, Plugin, Update},
+    ecs::{
+        query::With,
+        resource::Resource,
+        schedule::IntoScheduleConfigs,
+        system::{Commands, Query, Res, ResMut},
+        world::World,
+    },
+    input::{mouse::MouseWheel, ButtonInput},
+    prelude::{KeyCode, MessageReader, MouseButton},
+    state::condition::in_state,
+    time::{Time, Virtual},
+    transform::components::Transform,
+};
+use std::{path::Path, time::Duration};
+
+/// The last session is always recorded, so it can be attached to a bug report
+const RECORD_FILE: &str = "last_session.replay";
+const CHECKSUM_INTERVAL: u32 = 60;
+const FAST_FORWARD_SPEED: f32 = 8.0;
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
+
+pub enum ReplayPlugin {
+    Record,
+    Play(Replay),
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match self {
+            Self::Record => {
+                app.insert_resource(ReplayRecorder {
+                    writer: None,
+                    tick: 0,
+                });
+
+                app.add_state_system_enter(AppState::Game, start_recording);
+                app.add_state_system_fixed(AppState::Game, record.before(PlayerSystems));
+                app.add_systems(FixedLast, record_checksum.run_if(in_state(AppState::Game)));
+            }
+            Self::Play(replay) => {
+                app.insert_resource(ReplayPlayer {
+                    replay: replay.clone(),
+                    tick: 0,
+                    input_index: 0,
+                    input_repeated: 0,
+                    checksum_index: 0,
+                    is_diverged: false,
+                    is_finished: false,
+                });
+
+                app.add_state_system_enter(AppState::Game, start_playing);
+                app.add_state_system_fixed(AppState::Game, play.before(PlayerSystems));
+                app.add_systems(FixedLast, check_checksum.run_if(in_state(AppState::Game)));
+                app.add_systems(Update, update_speed);
+            }
+        }
+    }
+}
+
+#[derive(Resource)]
+struct ReplayRecorder {
+    writer: Option<ReplayWriter>,
+    tick: u32,
+}
+
+#[derive(Resource)]
+struct ReplayPlayer {
+    replay: Replay,
+    tick: u32,
+    input_index: usize,
+    input_repeated: u16,
+    checksum_index: usize,
+    is_diverged: bool,
+    is_finished: bool,
+}
+
+impl ReplayPlayer {
+    fn next_input(&mut self) -> Option<&[Input]> {
+        let (count, input) = self.replay.inputs.get(self.input_index)?;
+        self.input_repeated += 1;
+
+        if self.input_repeated >= *count {
+            self.input_index += 1;
+            self.input_repeated = 0;
+        }
+
+        return Some(input);
+    }
+
+    fn expected_checksum(&mut self) -> Option<u64> {
+        while let Some((tick, checksum)) = self.replay.checksums.get(self.checksum_index) {
+            if *tick > self.tick {
+                break;
+            }
+
+            self.checksum_index += 1;
+
+            if *tick == self.tick {
+                return Some(*checksum);
+            }
+        }
+
+        return None;
+    }
+}
+
+fn start_recording(world: &mut World) {
+    let seed = world.resource::<RngResource>().seed();
+    let settings = world.resource::<Settings>();
+
+    match ReplayWriter::create(Path::new(RECORD_FILE), seed, settings) {
+        Ok(writer) => {
+            world.resource_mut::<ReplayRecorder>().writer = Some(writer);
+        }
+        Err(error) => {
+            log::error!("{:?}", error);
+            log::warn!("The session won't be recorded");
+        }
+    }
+}
+
+fn record(mut recorder: ResMut<ReplayRecorder>, input: Res<PlayerInput>) {
+    let Some(writer) = &mut recorder.writer else {
+        return;
+    };
+
+    if let Err(error) = writer.add_input(input.pending()) {
+        log::error!("Failed to record input: {:?}", error);
+        recorder.writer = None;
+    }
+}
+
+fn record_checksum(
+    mut recorder: ResMut<ReplayRecorder>,
+    actors: Query<(&Transform, &Health), With<Actor>>,
+) {
+    let tick = recorder.tick;
+    recorder.tick += 1;
+
+    if !tick.is_multiple_of(CHECKSUM_INTERVAL) {
+        return;
+    }
+
+    let Some(writer) = &mut recorder.writer else {
+        return;
+    };
+
+    if let Err(error) = writer.add_checksum(tick, calc_checksum(&actors)) {
+        log::error!("Failed to record checksum: {:?}", error);
+        recorder.writer = None;
+    }
+}
+
+fn start_playing(mut commands: Commands, settings: Res<Settings>) {
+    let fast_forward = settings.controls.bindings.hint(Control::FastForward, false);
+
+    commands.queue(Notify {
+        text: "Replay".into(),
+        text_small: format!("Hold {} to fast-forward", fast_forward).into(),
+        duration: NOTIFICATION_DURATION,
+    });
+}
+
+fn play(mut player: ResMut<ReplayPlayer>, mut input: ResMut<PlayerInput>, mut commands: Commands) {
+    if let Some(recorded) = player.next_input() {
+        input.replace(recorded);
+    } else if !player.is_finished {
+        player.is_finished = true;
+        log::info!("Replay finished at tick {}", player.tick);
+
+        commands.queue(Notify {
+            text: "Replay finished".into(),
+            text_small: "You're in control now".into(),
+            duration: NOTIFICATION_DURATION,
+        });
+    }
+}
+
+fn check_checksum(
+    mut player: ResMut<ReplayPlayer>,
+    actors: Query<(&Transform, &Health), With<Actor>>,
+    mut commands: Commands,
+) {
+    if let Some(expected) = player.expected_checksum() {
+        if !player.is_diverged && expected != calc_checksum(&actors) {
+            player.is_diverged = true;
+            log::warn!("Replay diverged from the record at tick {}", player.tick);
+
+            commands.queue(Notify {
+                text: "Replay diverged".into(),
+                text_small: format!("At tick {}, what you see didn't happen", player.tick).into(),
+                duration: NOTIFICATION_DURATION,
+            });
+        }
+    }
+
+    player.tick += 1;
+}
+
+fn update_speed(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut mouse_scroll: MessageReader<MouseWheel>,
+    settings: Res<Settings>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let buttons = Buttons {
+        keyboard: Some(&keyboard),
+        mouse: Some(&mouse),
+        wheel: mouse_scroll.read().map(|e| e.y).sum(),
+        gamepad: None,
+    };
+
+    if buttons.pressed(settings.controls.bindings.get(Control::FastForward)) {
+        time.set_relative_speed(FAST_FORWARD_SPEED);
+    } else {
+        time.set_relative_speed(1.0);
+    }
+}
+
+/// FNV-1a hash of the actors state. Not a cryptographic one, but enough to notice the divergence
+fn calc_checksum(actors: &Query<(&Transform, &Health), With<Actor>>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for (transform, health) in actors.iter() {
+        let values = [
+            transform.translation.x,
+            transform.translation.y,
+            transform.rotation.z,
+            transform.rotation.w,
+            health.get(),
+        ];
+
+        for value in values {
+            for byte in value.to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+
+    return hash;
+}
@@ -0,0 +1,162 @@
+use crate::plugin::{scenario::WavesSave, WeaponState};
+use anyhow::{ensure, Context, Result};
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, time::Duration};
+
+/// Increase on any change of the format, since restoring a save of another one could break the
+/// world instead of failing
+const VERSION: u32 = 7;
+
+/// A saved waves run. Times are relative to the moment of saving, since the time of the world
+/// which it's restored into starts anew. Durations are stored exactly, unlike in the configs
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SaveGame {
+    version: u32,
+    pub difficulty: f32,
+    /// State of the global generator to continue with
+    pub rng: Pcg32,
+    pub scenario: WavesSave,
+    #[serde(default)]
+    pub actors: Vec<ActorSave>,
+    #[serde(default)]
+    pub bonuses: Vec<BonusSave>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActorSave {
+    pub config: String,
+    pub skill: f32,
+    pub stamina: f32,
+    pub melee_in: Duration,
+    pub position: [f32; 2],
+    pub rotation: f32,
+    pub velocity: [f32; 2],
+    pub velocity_angular: f32,
+    pub mass: f32,
+    pub drag: f32,
+    pub resistance: f32,
+    pub health: f32,
+    pub is_bot: bool,
+    pub player: Option<PlayerSave>,
+    pub weapon: Option<WeaponSave>,
+    /// Weapons carried besides the one in hands
+    #[serde(default)]
+    pub inventory: Vec<StoredWeaponSave>,
+    /// Spare ammo by projectile
+    #[serde(default)]
+    pub reserve: Vec<ReserveSave>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerSave {
+    #[serde(default)]
+    pub index: u8,
+    pub is_controllable: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponSave {
+    pub config: String,
+    pub state: WeaponState,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StoredWeaponSave {
+    pub config: String,
+    pub ammo: u8,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReserveSave {
+    pub projectile: String,
+    pub ammo: u16,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BonusSave {
+    pub weapon: String,
+    /// Ammo left in a dropped weapon
+    pub ammo: Option<u8>,
+    pub position: [f32; 2],
+    pub expires_in: Duration,
+}
+
+/// Only the version is read first, since the rest of a save of another version may not even parse
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl SaveGame {
+    pub fn new(difficulty: f32, rng: Pcg32, scenario: WavesSave) -> Self {
+        return Self {
+            version: VERSION,
+            difficulty,
+            rng,
+            scenario,
+            actors: Vec::new(),
+            bonuses: Vec::new(),
+        };
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let context = || format!("Failed to load game {}", path.display());
+        let encoded = std::fs::read_to_string(path).with_context(context)?;
+        return Self::decode(&encoded).with_context(context);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let context = || format!("Failed to save game {}", path.display());
+        let encoded = toml::to_string(self).with_context(context)?;
+        std::fs::write(path, encoded).with_context(context)?;
+        return Ok(());
+    }
+
+    fn decode(encoded: &str) -> Result<Self> {
+        let header = toml::from_str::<Header>(encoded).context("Not a save file")?;
+
+        ensure!(
+            header.version == VERSION,
+            "Unsupported save version {}, expected {}",
+            header.version,
+            VERSION,
+        );
+
+        return Ok(toml::from_str(encoded)?);
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    fn decode_error(encoded: &str) -> String {
+        return format!("{:#}", SaveGame::decode(encoded).err().unwrap());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let encoded = format!("version = {}\nsomething = \"else\"\n", VERSION + 1);
+        let expected = format!(
+            "Unsupported save version {}, expected {}",
+            VERSION + 1,
+            VERSION,
+        );
+
+        assert_eq!(decode_error(&encoded), expected);
+    }
+
+    #[test]
+    fn not_a_save() {
+        assert!(decode_error("seed = 0").starts_with("Not a save file"));
+    }
+}
@@ -0,0 +1,453 @@
+mod file;
+
+pub use self::file::*;
+use crate::{
+    plugin::{
+        bot::{ActorBotSet, Bot},
+        kinetics::Kinetics,
+        player::{Player, PlayerSet},
+        scenario::{Scenario, WavesScenario},
+        Actor, ActorRegistry, ActorSet, AmmoReserve, Bonus, BonusSpawn, Buttons, Health, Inventory,
+        Notify, ProjectileConfig, Smoothing, Weapon, WeaponRegistry, WeaponSet,
+    },
+    resource::{Control, RngResource, Settings},
+    state::AppState,
+    util::ext::{AppExt, QuatExt},
+};
+use anyhow::{bail, Context, Result};
+use bevy::{
+    app::{App, Plugin},
+    ecs::{
+        query::Has,
+        resource::Resource,
+        schedule::{common_conditions::resource_exists, IntoScheduleConfigs},
+        system::{Command, Commands, Res},
+        world::World,
+    },
+    input::{mouse::MouseWheel, ButtonInput},
+    math::{Vec2, Vec3Swizzles},
+    prelude::{KeyCode, MessageReader, MouseButton},
+    time::{Fixed, Time},
+    transform::components::Transform,
+};
+use std::{path::Path, time::Duration};
+
+const SAVE_FILE: &str = "savegame.toml";
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
+
+/// Saves a waves run by a key press. If there is a save given, restores it instead of the actors
+/// the scenario would spawn
+pub struct SavePlugin(pub Option<SaveGame>);
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(save) = &self.0 {
+            app.insert_resource(SaveToRestore(save.clone()));
+            app.add_state_system_enter(AppState::Game, restore);
+        }
+
+        app.add_state_system(
+            AppState::Game,
+            on_update.run_if(resource_exists::<ButtonInput<KeyCode>>),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct SaveToRestore(SaveGame);
+
+fn on_update(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut mouse_scroll: MessageReader<MouseWheel>,
+    settings: Res<Settings>,
+    mut commands: Commands,
+) {
+    let buttons = Buttons {
+        keyboard: Some(&keyboard),
+        mouse: Some(&mouse),
+        wheel: mouse_scroll.read().map(|e| e.y).sum(),
+        gamepad: None,
+    };
+
+    if buttons.just_pressed(settings.controls.bindings.get(Control::Save)) {
+        commands.queue(save);
+    }
+}
+
+fn save(world: &mut World) {
+    let result = capture(world).and_then(|save| save.save(Path::new(SAVE_FILE)));
+
+    let notification = match result {
+        Ok(()) => Notify {
+            text: "Game saved".into(),
+            text_small: format!(
+                "To continue, start the game with {} {}",
+                crate::LOAD_ARG,
+                SAVE_FILE
+            )
+            .into(),
+            duration: NOTIFICATION_DURATION,
+        },
+        Err(error) => {
+            log::error!("{:?}", error);
+            Notify {
+                text: "Failed to save game".into(),
+                text_small: "See the log for details".into(),
+                duration: NOTIFICATION_DURATION,
+            }
+        }
+    };
+
+    notification.apply(world);
+}
+
+fn capture(world: &mut World) -> Result<SaveGame> {
+    let time = world.resource::<Time<Fixed>>().elapsed();
+    let difficulty = world.resource::<Settings>().game.difficulty;
+    let rng = (**world.resource::<RngResource>()).clone();
+
+    let mut scenario = world.resource_mut::<Scenario>();
+    let timeout = scenario.timeout(time);
+
+    let Some(waves) = scenario.logic::<WavesScenario>() else {
+        bail!("Only a waves run can be saved");
+    };
+
+    let mut save = SaveGame::new(difficulty, rng, waves.save(timeout));
+
+    for (
+        actor,
+        transform,
+        smoothing,
+        kinetics,
+        health,
+        weapon,
+        inventory,
+        reserve,
+        player,
+        is_bot,
+    ) in world
+        .query::<(
+            &Actor,
+            &Transform,
+            Option<&Smoothing>,
+            &Kinetics,
+            &Health,
+            Option<&Weapon>,
+            Option<&Inventory>,
+            Option<&AmmoReserve>,
+            Option<&Player>,
+            Has<Bot>,
+        )>()
+        .iter(world)
+    {
+        if !health.is_alive() {
+            continue; // it's going to be despawned anyway
+        }
+
+        // saved as simulated, rather than as shown between the ticks
+        let transform = smoothing.map_or(*transform, |s| s.simulated(transform));
+
+        save.actors.push(ActorSave {
+            config: actor.config.name.clone(),
+            skill: actor.skill,
+            stamina: actor.stamina,
+            melee_in: actor.melee_next.saturating_sub(time),
+            position: transform.translation.xy().to_array(),
+            rotation: transform.rotation.angle_z(),
+            velocity: kinetics.velocity.to_array(),
+            velocity_angular: kinetics.velocity_angular,
+            mass: kinetics.mass,
+            drag: kinetics.drag,
+            resistance: health.resistance(),
+            health: health.get(),
+            is_bot,
+            player: player.map(|p| PlayerSave {
+                index: p.index,
+                is_controllable: p.is_controllable,
+            }),
+            weapon: weapon.map(|w| WeaponSave {
+                config: w.config.name.clone(),
+                state: w.get_state(time),
+            }),
+            inventory: inventory.map_or_else(Vec::new, |i| {
+                return i
+                    .stored()
+                    .iter()
+                    .map(|(w, ammo)| StoredWeaponSave {
+                        config: w.name.clone(),
+                        ammo: *ammo,
+                    })
+                    .collect();
+            }),
+            reserve: reserve.map_or_else(Vec::new, |r| {
+                return r
+                    .all()
+                    .iter()
+                    .map(|(p, ammo)| ReserveSave {
+                        projectile: p.name.to_string(),
+                        ammo: *ammo,
+                    })
+                    .collect();
+            }),
+        });
+    }
+
+    for (bonus, transform) in world.query::<(&Bonus, &Transform)>().iter(world) {
+        save.bonuses.push(BonusSave {
+            weapon: bonus.weapon.name.clone(),
+            ammo: bonus.ammo,
+            position: transform.translation.xy().to_array(),
+            expires_in: bonus.expiration.saturating_sub(time),
+        });
+    }
+
+    return Ok(save);
+}
+
+fn restore(world: &mut World) {
+    let Some(SaveToRestore(save)) = world.remove_resource::<SaveToRestore>() else {
+        return;
+    };
+
+    if let Err(error) = restore_save(world, &save) {
+        log::error!("{:?}", error);
+        Notify {
+            text: "Failed to load game".into(),
+            text_small: "See the log for details".into(),
+            duration: NOTIFICATION_DURATION,
+        }
+        .apply(world);
+    }
+}
+
+fn restore_save(world: &mut World, save: &SaveGame) -> Result<()> {
+    let actors = world.resource::<ActorRegistry>();
+    let weapons = world.resource::<WeaponRegistry>();
+
+    // resolve all the configs first, so a broken save doesn't leave the world half restored
+    let mut actors_resolved = Vec::with_capacity(save.actors.len());
+    let mut bonuses_resolved = Vec::with_capacity(save.bonuses.len());
+
+    for actor in &save.actors {
+        let config = actors
+            .get(&actor.config)
+            .with_context(|| format!("Unknown actor `{}`", actor.config))?;
+
+        let weapon = match &actor.weapon {
+            Some(weapon) => Some((
+                weapons
+                    .get(&weapon.config)
+                    .with_context(|| format!("Unknown weapon `{}`", weapon.config))?,
+                &weapon.state,
+            )),
+            None => None,
+        };
+
+        let mut inventory = Vec::with_capacity(actor.inventory.len());
+
+        for stored in &actor.inventory {
+            let weapon = weapons
+                .get(&stored.config)
+                .with_context(|| format!("Unknown weapon `{}`", stored.config))?;
+
+            inventory.push((weapon, stored.ammo));
+        }
+
+        let mut reserve = Vec::with_capacity(actor.reserve.len());
+
+        for spare in &actor.reserve {
+            let projectile = ProjectileConfig::find(&spare.projectile)
+                .with_context(|| format!("Unknown projectile `{}`", spare.projectile))?;
+
+            reserve.push((projectile, spare.ammo));
+        }
+
+        actors_resolved.push((actor, config, weapon, inventory, reserve));
+    }
+
+    for bonus in &save.bonuses {
+        let weapon = weapons
+            .get(&bonus.weapon)
+            .with_context(|| format!("Unknown weapon `{}`", bonus.weapon))?;
+
+        bonuses_resolved.push((bonus, weapon));
+    }
+
+    let time = world.resource::<Time<Fixed>>().elapsed();
+    **world.resource_mut::<RngResource>() = save.rng.clone();
+
+    for (saved, config, weapon, inventory, reserve) in actors_resolved {
+        let entity = world.spawn_empty().id();
+
+        ActorSet {
+            entity,
+            config,
+            position: Vec2::from_array(saved.position),
+            rotation: saved.rotation,
+        }
+        .apply(world);
+
+        if let Some(mut actor) = world.get_mut::<Actor>(entity) {
+            actor.skill = saved.skill;
+            actor.stamina = saved.stamina;
+            actor.melee_next = time + saved.melee_in;
+        }
+
+        if let Some(player) = &saved.player {
+            PlayerSet {
+                entity,
+                index: player.index,
+                is_controllable: player.is_controllable,
+            }
+            .apply(world);
+        }
+
+        if let Some(mut stored) = world.get_mut::<Inventory>(entity) {
+            for (weapon, ammo) in inventory {
+                stored.put(weapon, ammo);
+            }
+        }
+
+        if let Some(mut spare) = world.get_mut::<AmmoReserve>(entity) {
+            for (projectile, ammo) in reserve {
+                spare.set(projectile, ammo);
+            }
+        }
+
+        if saved.is_bot {
+            ActorBotSet { entity }.apply(world);
+        }
+
+        if let Some((weapon, state)) = weapon {
+            WeaponSet {
+                entity,
+                weapon: Some(weapon),
+            }
+            .apply(world);
+
+            if let Some(mut weapon) = world.get_mut::<Weapon>(entity) {
+                weapon.set_state(state, time);
+            }
+        }
+
+        // the commands above adjust these, so they're restored at last
+        if let Some(mut kinetics) = world.get_mut::<Kinetics>(entity) {
+            kinetics.mass = saved.mass;
+            kinetics.drag = saved.drag;
+            kinetics.velocity = Vec2::from_array(saved.velocity);
+            kinetics.velocity_angular = saved.velocity_angular;
+        }
+
+        let mut health = Health::new(saved.resistance);
+        health.set(saved.health);
+        world.entity_mut(entity).insert(health);
+    }
+
+    for (saved, weapon) in bonuses_resolved {
+        BonusSpawn::with_weapon(Vec2::from_array(saved.position), weapon, saved.expires_in)
+            .with_ammo(saved.ammo)
+            .apply(world);
+    }
+
+    log::info!(
+        "Game loaded with {} actors and {} bonuses",
+        save.actors.len(),
+        save.bonuses.len(),
+    );
+
+    return Ok(());
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::{
+        headless,
+        plugin::{scenario::WavesScenario, ActorKind},
+        resource::GameSettings,
+    };
+    use bevy::{app::App, ecs::query::With, state::state::State};
+    use rand::RngExt;
+
+    fn build(save: Option<SaveGame>) -> App {
+        let settings = Settings {
+            game: GameSettings {
+                seed: 42,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let scenario = match &save {
+            Some(save) => WavesScenario::restore(&save.scenario),
+            None => WavesScenario::new("default", 1),
+        };
+
+        let mut app = headless::build(settings, Scenario::new(scenario));
+        app.add_plugins(SavePlugin(save));
+        return app;
+    }
+
+    fn run_till_game(app: &mut App) {
+        while app.world().resource::<State<AppState>>().get() != &AppState::Game {
+            app.update();
+        }
+
+        app.update();
+    }
+
+    fn summarize(world: &mut World) -> (usize, usize, Option<(f32, bool)>) {
+        let zombies = world
+            .query::<&Actor>()
+            .iter(world)
+            .filter(|a| a.config.kind == ActorKind::Zombie)
+            .count();
+
+        let bonuses = world.query::<&Bonus>().iter(world).count();
+
+        let player = world
+            .query_filtered::<(&Health, Option<&Weapon>), With<Player>>()
+            .iter(world)
+            .next()
+            .map(|(h, w)| (h.get(), w.is_some()));
+
+        return (zombies, bonuses, player);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("shooter_save_and_load.toml");
+        let mut app = build(None);
+        run_till_game(&mut app);
+
+        for _ in 0..60 * 20 {
+            app.update();
+        }
+
+        let save = capture(app.world_mut()).unwrap();
+        save.save(&path).unwrap();
+        let save = SaveGame::load(&path).unwrap();
+
+        // the generator continues exactly where it was
+        assert!(save.rng == **app.world().resource::<RngResource>());
+
+        let expected = summarize(app.world_mut());
+        assert!(expected.0 > 0);
+        assert!(expected.2.is_some());
+
+        let mut restored = build(Some(save));
+        run_till_game(&mut restored);
+        assert_eq!(summarize(restored.world_mut()), expected);
+
+        // the scenario rolls the next spawns as the uninterrupted run does
+        let roll = |app: &mut App| {
+            let save = capture(app.world_mut()).unwrap();
+            return save.scenario.rng().clone().random::<u64>();
+        };
+
+        assert_eq!(roll(&mut restored), roll(&mut app));
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     plugin::{
         camera_target::CameraTarget,
         scenario::{bench_utils::Bench, ScenarioLogic},
-        Actor, ActorConfig, ActorSet, ProjectileSpawn, WeaponConfig,
+        Actor, ActorConfig, ActorRegistry, ActorSet, ProjectileSpawn, WeaponConfig, WeaponRegistry,
     },
     util::{ext::QuatExt, Timer},
 };
@@ -17,16 +17,17 @@ use rand_pcg::Pcg32;
 use std::{any::Any, f32::consts::TAU, time::Duration};
 
 const SEED: u64 = 4487;
-const SHOT_INTERVAL: Duration = Duration::from_millis((60.0 / WEAPON.fire_rate * 1000.0) as u64);
 const SPAWN_BATCH: usize = 50;
 const SPAWN_MAX: usize = 1500;
 const WORLD_SIZE: f32 = 100.0;
-const WEAPON: &WeaponConfig = &WeaponConfig::AK_74M;
+const WEAPON: &str = "AK-74M";
 
 pub struct BenchProjectilesScenario {
     inner: Bench,
     rng: Pcg32,
     next_shot: Timer,
+    weapon: Option<&'static WeaponConfig>,
+    zombie: Option<&'static ActorConfig>,
 }
 
 impl Default for BenchProjectilesScenario {
@@ -35,12 +36,18 @@ impl Default for BenchProjectilesScenario {
             inner: Bench::default(),
             rng: Pcg32::seed_from_u64(SEED),
             next_shot: Timer::default(),
+            weapon: None,
+            zombie: None,
         };
     }
 }
 
 impl BenchProjectilesScenario {
     fn spawn_shooter(&mut self, commands: &mut Commands) {
+        let Some(zombie) = self.zombie else {
+            return;
+        };
+
         let entity = commands.spawn_empty().id();
         let s = WORLD_SIZE / 2.0;
         let x = self.rng.random_range(-s..s);
@@ -49,7 +56,7 @@ impl BenchProjectilesScenario {
 
         commands.queue(ActorSet {
             entity,
-            config: &ActorConfig::ZOMBIE,
+            config: zombie,
             position: Vec2::new(x, y),
             rotation: r,
         });
@@ -60,6 +67,9 @@ impl BenchProjectilesScenario {
 
 impl ScenarioLogic for BenchProjectilesScenario {
     fn on_enter(&mut self, _time: Duration, world: &mut World) -> Duration {
+        self.weapon = world.resource::<WeaponRegistry>().get(WEAPON);
+        self.zombie = world.resource::<ActorRegistry>().get("zombie");
+
         world
             .spawn_empty()
             .insert(Transform::default())
@@ -79,8 +89,18 @@ impl ScenarioLogic for BenchProjectilesScenario {
             }
         }
 
-        if self.next_shot.try_next_add(time, SHOT_INTERVAL) {
-            commands.queue(|world: &mut World| {
+        if self.inner.spawned > SPAWN_MAX {
+            self.inner.finish(commands);
+        }
+
+        let Some(weapon) = self.weapon else {
+            return;
+        };
+
+        let shot_interval = Duration::from_secs_f32(60.0 / weapon.fire_rate);
+
+        if self.next_shot.try_next_add(time, shot_interval) {
+            commands.queue(move |world: &mut World| {
                 let transforms = world
                     .query_filtered::<&Transform, With<Actor>>()
                     .iter(world)
@@ -93,19 +113,15 @@ impl ScenarioLogic for BenchProjectilesScenario {
 
                 for (position, rotation) in transforms {
                     ProjectileSpawn {
-                        config: WEAPON.projectile,
+                        config: weapon.projectile,
                         position,
-                        velocity: rotation * WEAPON.muzzle_velocity,
+                        velocity: rotation * weapon.muzzle_velocity,
                         shooter: None,
                     }
                     .apply(world);
                 }
             });
         }
-
-        if self.inner.spawned > SPAWN_MAX {
-            self.inner.finish(commands);
-        }
     }
 
     fn as_any_mut(&mut self) -> &mut dyn Any {
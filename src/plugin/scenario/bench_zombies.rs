@@ -4,7 +4,7 @@ use crate::{
         bot::ActorBotSet,
         player::PlayerSpawn,
         scenario::{bench_utils::Bench, ScenarioLogic},
-        ActorConfig, ActorSet, WeaponConfig,
+        ActorConfig, ActorRegistry, ActorSet, WeaponRegistry,
     },
 };
 use bevy::{
@@ -20,16 +20,21 @@ const SPAWN_MAX: usize = 3000;
 #[derive(Default)]
 pub struct BenchZombiesScenario {
     inner: Bench,
+    zombie: Option<&'static ActorConfig>,
 }
 
 impl BenchZombiesScenario {
     fn spawn_batch(&mut self, commands: &mut Commands) {
+        let Some(zombie) = self.zombie else {
+            return;
+        };
+
         for _ in 0..SPAWN_BATCH {
             let entity = commands.spawn_empty().id();
 
             commands.queue(ActorSet {
                 entity,
-                config: &ActorConfig::ZOMBIE,
+                config: zombie,
                 position: Vec2::ZERO,
                 rotation: 0.0,
             });
@@ -44,11 +49,13 @@ impl BenchZombiesScenario {
 impl ScenarioLogic for BenchZombiesScenario {
     fn on_enter(&mut self, _time: Duration, world: &mut World) -> Duration {
         ForestMap.generate(world);
+        self.zombie = world.resource::<ActorRegistry>().get("zombie");
 
         // TODO: just spawn spectator
         PlayerSpawn {
-            config: &ActorConfig::HUMAN,
-            weapon: &WeaponConfig::AKS_74U,
+            config: world.resource::<ActorRegistry>().get("human"),
+            weapon: world.resource::<WeaponRegistry>().get("AKS-74U"),
+            index: 0,
             is_controllable: false,
         }
         .apply(world);
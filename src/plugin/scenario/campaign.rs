@@ -0,0 +1,241 @@
+use crate::{
+    data::VIEW_DISTANCE,
+    plugin::{scenario::FriendlyFire, ActorConfig, ActorRegistry, WeaponConfig, WeaponRegistry},
+    util::serde_secs,
+};
+use anyhow::{bail, ensure, Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+const DIRECTORY: &str = "assets/campaigns";
+
+/// A list of waves loaded from `assets/campaigns/<name>.toml` file with all the actor and weapon
+/// names resolved
+pub struct Campaign {
+    pub waves: Vec<Wave>,
+    /// An endless wave which starts once all the waves are completed
+    pub bonus_wave: Option<Wave>,
+    pub friendly_fire: FriendlyFire,
+}
+
+pub struct Wave {
+    pub size: u16,
+    pub spawn_interval: Duration,
+    pub spawn_distance: f32,
+    /// Expected number of bonuses dropped by enemies during the wave
+    pub bonuses: f32,
+    pub intro: Option<String>,
+    pub intro_small: Option<String>,
+    pub spawns: Vec<WaveSpawn>,
+    pub support: Option<WaveSupport>,
+}
+
+pub struct WaveSpawn {
+    pub actor: &'static ActorConfig,
+    pub weapon: Option<&'static WeaponConfig>,
+    pub weight: f64,
+}
+
+/// Allies which come on wave start, each with a random weapon
+pub struct WaveSupport {
+    pub actor: &'static ActorConfig,
+    pub size: u8,
+}
+
+impl Campaign {
+    pub fn load(name: &str, actors: &ActorRegistry, weapons: &WeaponRegistry) -> Result<Self> {
+        let path = format!("{}/{}.toml", DIRECTORY, name);
+        let context = || format!("Failed to load campaign {}", path);
+        let encoded = std::fs::read_to_string(&path).with_context(context)?;
+        let config = toml::from_str::<CampaignConfig>(&encoded).with_context(context)?;
+        return Self::resolve(config, actors, weapons).with_context(context);
+    }
+
+    fn resolve(
+        config: CampaignConfig,
+        actors: &ActorRegistry,
+        weapons: &WeaponRegistry,
+    ) -> Result<Self> {
+        ensure!(!config.waves.is_empty(), "Campaign has no waves");
+
+        let mut waves = Vec::with_capacity(config.waves.len());
+
+        for (i, wave) in config.waves.into_iter().enumerate() {
+            waves.push(
+                Wave::resolve(wave, actors, weapons)
+                    .with_context(|| format!("Invalid wave {}", i + 1))?,
+            );
+        }
+
+        let bonus_wave = match config.bonus_wave {
+            Some(wave) => Some(Wave::resolve(wave, actors, weapons).context("Invalid bonus wave")?),
+            None => None,
+        };
+
+        return Ok(Self {
+            waves,
+            bonus_wave,
+            friendly_fire: config.friendly_fire,
+        });
+    }
+}
+
+impl Wave {
+    fn resolve(
+        config: WaveConfig,
+        actors: &ActorRegistry,
+        weapons: &WeaponRegistry,
+    ) -> Result<Self> {
+        ensure!(config.size > 0, "Wave size must be greater than zero");
+        ensure!(!config.spawns.is_empty(), "Wave has no spawns");
+        ensure!(config.bonuses >= 0.0, "Bonuses must be non-negative");
+
+        let mut spawns = Vec::with_capacity(config.spawns.len());
+
+        for spawn in config.spawns {
+            ensure!(spawn.weight > 0.0, "Spawn weight must be greater than zero");
+
+            let weapon = match spawn.weapon {
+                Some(name) => Some(resolve_weapon(&name, weapons)?),
+                None => None,
+            };
+
+            spawns.push(WaveSpawn {
+                actor: resolve_actor(&spawn.actor, actors)?,
+                weapon,
+                weight: spawn.weight,
+            });
+        }
+
+        let support = match config.support {
+            Some(support) => Some(WaveSupport {
+                actor: resolve_actor(&support.actor, actors)?,
+                size: support.size,
+            }),
+            None => None,
+        };
+
+        return Ok(Self {
+            size: config.size,
+            spawn_interval: config.spawn_interval,
+            spawn_distance: config.spawn_distance,
+            bonuses: config.bonuses,
+            intro: config.intro,
+            intro_small: config.intro_small,
+            spawns,
+            support,
+        });
+    }
+}
+
+fn resolve_actor(name: &str, actors: &ActorRegistry) -> Result<&'static ActorConfig> {
+    let Some(actor) = actors.get(name) else {
+        bail!("Unknown actor `{}`", name);
+    };
+
+    return Ok(actor);
+}
+
+fn resolve_weapon(name: &str, weapons: &WeaponRegistry) -> Result<&'static WeaponConfig> {
+    let Some(weapon) = weapons.get(name) else {
+        bail!("Unknown weapon `{}`", name);
+    };
+
+    return Ok(weapon);
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CampaignConfig {
+    waves: Vec<WaveConfig>,
+    #[serde(default)]
+    bonus_wave: Option<WaveConfig>,
+    #[serde(default)]
+    friendly_fire: FriendlyFire,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveConfig {
+    size: u16,
+    #[serde(default = "WaveConfig::default_spawn_interval", with = "serde_secs")]
+    spawn_interval: Duration,
+    #[serde(default = "WaveConfig::default_spawn_distance")]
+    spawn_distance: f32,
+    #[serde(default)]
+    bonuses: f32,
+    #[serde(default)]
+    intro: Option<String>,
+    #[serde(default)]
+    intro_small: Option<String>,
+    spawns: Vec<WaveSpawnConfig>,
+    #[serde(default)]
+    support: Option<WaveSupportConfig>,
+}
+
+impl WaveConfig {
+    fn default_spawn_interval() -> Duration {
+        return Duration::from_millis(700);
+    }
+
+    fn default_spawn_distance() -> f32 {
+        return VIEW_DISTANCE * 0.5;
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveSpawnConfig {
+    actor: String,
+    #[serde(default)]
+    weapon: Option<String>,
+    #[serde(default = "WaveSpawnConfig::default_weight")]
+    weight: f64,
+}
+
+impl WaveSpawnConfig {
+    fn default_weight() -> f64 {
+        return 1.0;
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveSupportConfig {
+    actor: String,
+    size: u8,
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_default() {
+        let actors = ActorRegistry::load().unwrap();
+        let weapons = WeaponRegistry::load().unwrap();
+
+        let campaign = Campaign::load("default", &actors, &weapons).unwrap();
+
+        assert_eq!(campaign.waves.len(), 7);
+        assert!(campaign.bonus_wave.is_some());
+        assert_eq!(campaign.friendly_fire, FriendlyFire::Off);
+    }
+
+    #[test]
+    fn unknown_actor() {
+        let actors = ActorRegistry::load().unwrap();
+        let weapons = WeaponRegistry::load().unwrap();
+
+        let config = toml::from_str::<CampaignConfig>(
+            "[[waves]]\nsize = 1\nspawns = [{ actor = \"nobody\" }]",
+        )
+        .unwrap();
+
+        let error = Campaign::resolve(config, &actors, &weapons).err().unwrap();
+        let error = format!("{:#}", error);
+
+        assert_eq!(error, "Invalid wave 1: Unknown actor `nobody`");
+    }
+}
@@ -1,11 +1,15 @@
 mod bench_projectiles;
 mod bench_utils;
 mod bench_zombies;
+mod campaign;
+mod remote;
 mod test;
 mod test_bot_spread;
 mod waves;
 
-pub use self::{bench_projectiles::*, bench_zombies::*, test::*, test_bot_spread::*, waves::*};
+pub use self::{
+    bench_projectiles::*, bench_zombies::*, remote::*, test::*, test_bot_spread::*, waves::*,
+};
 use crate::{plugin::ActorDeathEvent, util::ext::AppExt, AppState};
 use bevy::{
     ecs::{
@@ -14,16 +18,18 @@ use bevy::{
         world::{Mut, World},
     },
     prelude::{App, Commands, MessageReader, Plugin},
-    time::Time,
+    time::{Fixed, Time},
 };
+use serde::Deserialize;
 use std::{any::Any, time::Duration};
 
 pub struct ScenarioPlugin;
 
 impl Plugin for ScenarioPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<FriendlyFire>();
         app.add_state_system_enter(AppState::Game, on_enter);
-        app.add_state_system(AppState::Game, on_update);
+        app.add_state_system_fixed(AppState::Game, on_update);
     }
 }
 
@@ -41,9 +47,41 @@ impl Scenario {
         };
     }
 
-    fn logic<T: ScenarioLogic + 'static>(&mut self) -> Option<&mut T> {
+    pub fn logic<T: ScenarioLogic + 'static>(&mut self) -> Option<&mut T> {
         return self.logic.as_mut().as_any_mut().downcast_mut::<T>();
     }
+
+    /// Time left till the next interval update
+    pub fn timeout(&self, time: Duration) -> Duration {
+        return self.timer.saturating_sub(time);
+    }
+}
+
+/// Whether actors hurt the ones of their own kind. Set by the scenario on enter
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FriendlyFire {
+    #[default]
+    Off,
+    Reduced,
+    Full,
+}
+
+impl FriendlyFire {
+    const REDUCED_DAMAGE: f32 = 0.3;
+
+    pub fn is_on(self) -> bool {
+        return self != Self::Off;
+    }
+
+    /// Share of the damage an ally takes
+    pub fn damage_factor(self) -> f32 {
+        match self {
+            Self::Off => return 0.0,
+            Self::Reduced => return Self::REDUCED_DAMAGE,
+            Self::Full => return 1.0,
+        }
+    }
 }
 
 pub trait ScenarioLogic {
@@ -51,6 +89,10 @@ pub trait ScenarioLogic {
         return Duration::ZERO;
     }
 
+    fn friendly_fire(&self) -> FriendlyFire {
+        return FriendlyFire::Off;
+    }
+
     fn on_actor_death(&mut self, _event: &ActorDeathEvent, _commands: &mut Commands) {}
 
     fn on_player_death(&mut self, _event: &ActorDeathEvent, _commands: &mut Commands) {}
@@ -66,9 +108,11 @@ pub trait ScenarioLogic {
 
 fn on_enter(world: &mut World) {
     world.resource_scope(|world, mut scenario: Mut<Scenario>| {
-        let time = world.resource::<Time>().elapsed();
+        // the scenario is updated by the simulation ticks, so use their time to keep it repeatable
+        let time = world.resource::<Time<Fixed>>().elapsed();
         let timeout = scenario.logic.on_enter(time, world);
         scenario.timer = time + timeout;
+        world.insert_resource(scenario.logic.friendly_fire());
     });
 }
 
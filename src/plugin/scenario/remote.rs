@@ -0,0 +1,20 @@
+use crate::{
+    map::{ForestMap, Map},
+    plugin::scenario::ScenarioLogic,
+};
+use bevy::ecs::world::World;
+use std::{any::Any, time::Duration};
+
+/// The world of a network client. Only the map is generated, the rest comes from the server
+pub struct RemoteScenario;
+
+impl ScenarioLogic for RemoteScenario {
+    fn on_enter(&mut self, _time: Duration, world: &mut World) -> Duration {
+        ForestMap.generate(world);
+        return Duration::ZERO;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        return self;
+    }
+}
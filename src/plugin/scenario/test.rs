@@ -1,6 +1,10 @@
 use crate::{
     map::{Map, TestMap},
-    plugin::{player::PlayerSpawn, scenario::ScenarioLogic, ActorConfig, WeaponConfig},
+    plugin::{
+        player::PlayerSpawn,
+        scenario::{FriendlyFire, ScenarioLogic},
+        ActorRegistry, WeaponRegistry,
+    },
 };
 use bevy::ecs::{system::Command, world::World};
 use std::{any::Any, time::Duration};
@@ -8,12 +12,17 @@ use std::{any::Any, time::Duration};
 pub struct TestScenario;
 
 impl ScenarioLogic for TestScenario {
+    fn friendly_fire(&self) -> FriendlyFire {
+        return FriendlyFire::Full;
+    }
+
     fn on_enter(&mut self, _time: Duration, world: &mut World) -> Duration {
         TestMap.generate(world);
 
         PlayerSpawn {
-            config: &ActorConfig::HUMAN,
-            weapon: &WeaponConfig::AKS_74U,
+            config: world.resource::<ActorRegistry>().get("human"),
+            weapon: world.resource::<WeaponRegistry>().get("AKS-74U"),
+            index: 0,
             is_controllable: true,
         }
         .apply(world);
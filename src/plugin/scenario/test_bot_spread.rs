@@ -1,7 +1,7 @@
 use crate::{
     map::{Map, TestMap},
     plugin::{
-        bot::ActorBotSet, camera_target::CameraTarget, scenario::ScenarioLogic, ActorConfig,
+        bot::ActorBotSet, camera_target::CameraTarget, scenario::ScenarioLogic, ActorRegistry,
         ActorSet,
     },
 };
@@ -23,12 +23,16 @@ impl ScenarioLogic for TestBotSpreadScenario {
             .insert(Transform::default())
             .insert(CameraTarget::default());
 
+        let Some(zombie) = world.resource::<ActorRegistry>().get("zombie") else {
+            return Duration::ZERO;
+        };
+
         for _ in 0..128 {
             let entity = world.spawn_empty().id();
 
             ActorSet {
                 entity,
-                config: &ActorConfig::ZOMBIE,
+                config: zombie,
                 position: Vec2::ZERO,
                 rotation: 0.0,
             }
@@ -1,92 +1,37 @@
 use crate::{
-    data::VIEW_DISTANCE,
     map::{ForestMap, Map},
     plugin::{
         bot::ActorBotSet,
         player::{Player, PlayerSpawn},
-        scenario::{Scenario, ScenarioLogic},
-        Actor, ActorConfig, ActorDeathEvent, ActorKind, ActorSet, BonusSpawn, Health, Notify,
-        WeaponConfig, WeaponSet,
+        scenario::{
+            campaign::{Campaign, Wave},
+            FriendlyFire, Scenario, ScenarioLogic,
+        },
+        Actor, ActorConfig, ActorDeathEvent, ActorKind, ActorRegistry, ActorSet, BonusSpawn,
+        Health, Notify, WeaponConfig, WeaponRegistry, WeaponSet,
     },
+    resource::{Control, RngResource, Settings},
     util::ext::Vec2Ext,
 };
 use bevy::{
     ecs::{query::With, system::Command},
+    input::gamepad::Gamepad,
     math::{Vec2, Vec3Swizzles},
     prelude::{Commands, World},
     transform::components::Transform,
 };
 use rand::{seq::IndexedRandom, RngExt, SeedableRng};
 use rand_pcg::Pcg32;
-use std::{any::Any, f32::consts::PI, time::Duration};
-
-const SPAWN_CHANCE_LOW: f64 = 0.02;
-const SPAWN_CHANCE_MILD: f64 = 0.1;
-const SPAWN_CHANCE_HIGH: f64 = 0.3;
-
-const WAVES: &[Wave] = &[
-    // melee zombies only
-    Wave {
-        size: 5,
-        pistol_chance: 0.0,
-        rifle_chance: 0.0,
-        agile_chance: 0.0,
-    },
-    Wave {
-        size: 25,
-        pistol_chance: 0.0,
-        rifle_chance: 0.0,
-        agile_chance: 0.0,
-    },
-    // agile zombies
-    Wave {
-        size: 50,
-        pistol_chance: 0.0,
-        rifle_chance: 0.0,
-        agile_chance: SPAWN_CHANCE_MILD,
-    },
-    Wave {
-        size: 75,
-        pistol_chance: 0.0,
-        rifle_chance: 0.0,
-        agile_chance: SPAWN_CHANCE_HIGH,
-    },
-    // zombies with pistols
-    Wave {
-        size: 100,
-        pistol_chance: SPAWN_CHANCE_MILD,
-        rifle_chance: 0.0,
-        agile_chance: SPAWN_CHANCE_LOW,
-    },
-    Wave {
-        size: 125,
-        pistol_chance: SPAWN_CHANCE_HIGH,
-        rifle_chance: 0.0,
-        agile_chance: SPAWN_CHANCE_LOW,
-    },
-    // zombies with rifles
-    Wave {
-        size: 150,
-        pistol_chance: SPAWN_CHANCE_HIGH,
-        rifle_chance: SPAWN_CHANCE_MILD,
-        agile_chance: SPAWN_CHANCE_LOW,
-    },
-];
-
-const WAVE_BONUS: Wave = Wave {
-    size: u16::MAX,
-    pistol_chance: SPAWN_CHANCE_LOW,
-    rifle_chance: 0.0,
-    agile_chance: SPAWN_CHANCE_MILD,
-};
+use serde::{Deserialize, Serialize};
+use std::{any::Any, f32::consts::PI, sync::Arc, time::Duration};
 
-const ENEMY_SPAWN_DISTANCE: f32 = VIEW_DISTANCE * 0.5;
-const BONUSES_PER_WAVE: f32 = 3.0;
 const GAME_OVER_TEXT_DURATION: Duration = Duration::from_secs(8);
 const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
-const WAVE_BONUS_HUMANS: u8 = 16;
-const SPAWN_INTERVAL: Duration = Duration::from_millis(700);
+const PLAYER_ACTOR: &str = "human";
+const PLAYER_WEAPON_FALLBACK: &str = "IZh-27";
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum Task {
     StartNextWave,
     SpawnZombie,
@@ -95,10 +40,10 @@ enum Task {
 }
 
 impl Task {
-    fn get_timeout(&self) -> Duration {
+    fn get_timeout(&self, wave: Option<&Wave>) -> Duration {
         return match self {
             Self::StartNextWave => DEFAULT_INTERVAL,
-            Self::SpawnZombie => SPAWN_INTERVAL,
+            Self::SpawnZombie => wave.map_or(DEFAULT_INTERVAL, |w| w.spawn_interval),
             Self::CheckWaveCompletion => DEFAULT_INTERVAL,
             Self::CompleteWave => Duration::from_secs(4),
         };
@@ -106,54 +51,123 @@ impl Task {
 }
 
 pub struct WavesScenario {
+    task: Task,
+    wave_index: u8,
+    zombies_spawned: u16,
+    kills: u16,
+    /// Forked from the global generator on enter
+    rng: Pcg32,
+    campaign_name: String,
+    campaign: Arc<Campaign>,
+    weapons: Vec<&'static WeaponConfig>,
+    /// Set if the run is restored from a save. Then the actors are restored as well, rather than
+    /// spawned by the scenario
+    restored_timeout: Option<Duration>,
+}
+
+/// State of a waves run, see `SaveGame`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WavesSave {
+    campaign: String,
     task: Task,
     wave_index: u8,
     zombies_spawned: u16,
     kills: u16,
     rng: Pcg32,
+    timeout: Duration,
+}
+
+impl WavesSave {
+    #[cfg(test)]
+    pub fn rng(&self) -> &Pcg32 {
+        return &self.rng;
+    }
 }
 
 impl WavesScenario {
-    pub fn new(level: u8) -> Self {
+    pub fn new(campaign: &str, level: u8) -> Self {
         return Self {
             task: Task::StartNextWave,
             wave_index: level.saturating_sub(1),
             zombies_spawned: 0,
             kills: 0,
-            rng: Pcg32::seed_from_u64(32),
+            rng: Pcg32::seed_from_u64(0),
+            campaign_name: campaign.to_string(),
+            campaign: Arc::new(Campaign {
+                waves: Vec::new(),
+                bonus_wave: None,
+                friendly_fire: FriendlyFire::Off,
+            }),
+            weapons: Vec::new(),
+            restored_timeout: None,
+        };
+    }
+
+    pub fn restore(save: &WavesSave) -> Self {
+        return Self {
+            task: save.task,
+            wave_index: save.wave_index,
+            zombies_spawned: save.zombies_spawned,
+            kills: save.kills,
+            rng: save.rng.clone(),
+            restored_timeout: Some(save.timeout),
+            ..Self::new(&save.campaign, 0)
+        };
+    }
+
+    pub fn save(&self, timeout: Duration) -> WavesSave {
+        return WavesSave {
+            campaign: self.campaign_name.clone(),
+            task: self.task,
+            wave_index: self.wave_index,
+            zombies_spawned: self.zombies_spawned,
+            kills: self.kills,
+            rng: self.rng.clone(),
+            timeout,
         };
     }
 
     fn update(&mut self, commands: &mut Commands) -> Task {
-        let wave = self.wave();
+        let campaign = Arc::clone(&self.campaign);
+
+        let Some(wave) = self.wave(&campaign) else {
+            // campaign is completed and has no bonus wave, so nothing to do more
+            return Task::StartNextWave;
+        };
 
         match self.task {
             Task::StartNextWave => {
                 self.zombies_spawned = 0;
                 self.kills = 0;
 
-                if self.is_wave_bonus() {
-                    commands.queue(Notify {
-                        text: "Bonus wave".into(),
-                        text_small: "How long will you stay? Support is on the way...".into(),
-                        ..Default::default()
-                    });
-
+                commands.queue(Notify {
+                    text: wave
+                        .intro
+                        .clone()
+                        .unwrap_or_else(|| {
+                            format!("Wave {}/{}", self.wave_number(), campaign.waves.len())
+                        })
+                        .into(),
+                    text_small: wave
+                        .intro_small
+                        .clone()
+                        .unwrap_or_else(|| format!("Kill {} zombies", wave.size))
+                        .into(),
+                    ..Default::default()
+                });
+
+                if let Some(support) = &wave.support {
                     let direction = self.rng.random_range(-PI..PI);
-                    for _ in 0..WAVE_BONUS_HUMANS {
+
+                    for _ in 0..support.size {
                         commands.queue(SpawnActor {
                             direction,
-                            distance: ENEMY_SPAWN_DISTANCE,
-                            config: &ActorConfig::HUMAN,
-                            weapon: WeaponConfig::ALL.choose(&mut self.rng),
+                            distance: wave.spawn_distance,
+                            config: support.actor,
+                            weapon: self.weapons.choose(&mut self.rng).copied(),
                         });
                     }
-                } else {
-                    commands.queue(Notify {
-                        text: format!("Wave {}/{}", self.wave_number(), WAVES.len()).into(),
-                        text_small: format!("Kill {} zombies", wave.size).into(),
-                        ..Default::default()
-                    });
                 }
 
                 commands.queue(heal_humans);
@@ -162,22 +176,17 @@ impl WavesScenario {
             Task::SpawnZombie => {
                 log::debug!("Spawning a zombie");
 
-                let mut spawn = SpawnActor {
-                    direction: self.rng.random_range(-PI..PI),
-                    distance: ENEMY_SPAWN_DISTANCE,
-                    config: &ActorConfig::ZOMBIE,
-                    weapon: None,
-                };
-
-                if self.rng.random_bool(wave.agile_chance) {
-                    spawn.config = &ActorConfig::ZOMBIE_AGILE;
-                } else if self.rng.random_bool(wave.rifle_chance) {
-                    spawn.weapon = Some(&WeaponConfig::AKS_74U_BROKEN);
-                } else if self.rng.random_bool(wave.pistol_chance) {
-                    spawn.weapon = Some(&WeaponConfig::PM_BROKEN);
+                let direction = self.rng.random_range(-PI..PI);
+
+                if let Ok(spawn) = wave.spawns.choose_weighted(&mut self.rng, |s| s.weight) {
+                    commands.queue(SpawnActor {
+                        direction,
+                        distance: wave.spawn_distance,
+                        config: spawn.actor,
+                        weapon: spawn.weapon,
+                    });
                 }
 
-                commands.queue(spawn);
                 self.zombies_spawned += 1;
 
                 if self.zombies_spawned < wave.size {
@@ -187,7 +196,7 @@ impl WavesScenario {
                 }
             }
             Task::CheckWaveCompletion => {
-                commands.queue(count_zombies);
+                commands.queue(count_enemies);
                 log::trace!("Checking for wave completion");
                 return Task::CheckWaveCompletion;
             }
@@ -195,8 +204,11 @@ impl WavesScenario {
                 if self.is_wave_last() {
                     commands.queue(Notify {
                         text: "Congratulations!".into(),
-                        text_small: format!("You've completed the all {} waves", WAVES.len())
-                            .into(),
+                        text_small: format!(
+                            "You've completed the all {} waves",
+                            campaign.waves.len(),
+                        )
+                        .into(),
                         ..Default::default()
                     });
                 } else {
@@ -213,10 +225,11 @@ impl WavesScenario {
         }
     }
 
-    fn wave(&self) -> &'static Wave {
-        return WAVES
+    fn wave<'a>(&self, campaign: &'a Campaign) -> Option<&'a Wave> {
+        return campaign
+            .waves
             .get(usize::from(self.wave_index))
-            .unwrap_or(&WAVE_BONUS);
+            .or(campaign.bonus_wave.as_ref());
     }
 
     fn wave_number(&self) -> u8 {
@@ -224,19 +237,51 @@ impl WavesScenario {
     }
 
     fn is_wave_last(&self) -> bool {
-        return usize::from(self.wave_number()) == WAVES.len();
-    }
-
-    fn is_wave_bonus(&self) -> bool {
-        return usize::from(self.wave_index) == WAVES.len();
+        return usize::from(self.wave_number()) == self.campaign.waves.len();
     }
 }
 
 impl ScenarioLogic for WavesScenario {
+    fn friendly_fire(&self) -> FriendlyFire {
+        return self.campaign.friendly_fire;
+    }
+
     fn on_enter(&mut self, _time: Duration, world: &mut World) -> Duration {
         ForestMap.generate(world);
 
-        let weapon = WeaponConfig::ALL
+        if self.restored_timeout.is_none() {
+            self.rng = world.resource_mut::<RngResource>().fork();
+        }
+
+        let actors = world.resource::<ActorRegistry>();
+        let weapons = world.resource::<WeaponRegistry>();
+
+        match Campaign::load(&self.campaign_name, actors, weapons) {
+            Ok(campaign) => {
+                self.campaign = Arc::new(campaign);
+            }
+            Err(error) => {
+                log::error!("{:?}", error);
+                Notify {
+                    text: "Failed to load campaign".into(),
+                    text_small: "See the log for details".into(),
+                    duration: GAME_OVER_TEXT_DURATION,
+                }
+                .apply(world);
+            }
+        }
+
+        let actors = world.resource::<ActorRegistry>();
+        let weapons = world.resource::<WeaponRegistry>();
+        let player = actors.get(PLAYER_ACTOR);
+        self.weapons = weapons.available().collect();
+
+        if let Some(timeout) = self.restored_timeout {
+            return timeout;
+        }
+
+        let weapon = self
+            .weapons
             .iter()
             .find(|w| {
                 let wave = self.wave_number();
@@ -247,74 +292,68 @@ impl ScenarioLogic for WavesScenario {
                     return w.level == wave.saturating_sub(1); // give weapon of previous level
                 }
             })
-            .unwrap_or(&WeaponConfig::IZH_27);
-
-        PlayerSpawn {
-            config: &ActorConfig::HUMAN,
-            weapon,
-            is_controllable: true,
+            .copied()
+            .or_else(|| weapons.get(PLAYER_WEAPON_FALLBACK));
+
+        for index in 0..world.resource::<Settings>().game.players {
+            PlayerSpawn {
+                config: player,
+                weapon,
+                index,
+                is_controllable: true,
+            }
+            .apply(world);
         }
-        .apply(world);
 
         return DEFAULT_INTERVAL;
     }
 
     fn on_actor_death(&mut self, event: &ActorDeathEvent, commands: &mut Commands) {
-        if let ActorKind::Zombie = event.kind {
+        // the wave is made of zombies and bandits
+        if event.kind != ActorKind::Human {
             self.kills += 1;
 
             if self.kills == 1 {
                 match self.wave_index {
                     0 => {
-                        commands.queue(Notify {
-                            text_small: "Press [R] to reload".into(),
-                            ..Default::default()
-                        });
+                        commands.queue(notify_hint(&[Control::Reload], "to reload"));
                     }
                     1 => {
-                        commands.queue(Notify {
-                            text_small: "Press [RMB] to aim".into(),
-                            ..Default::default()
-                        });
+                        commands.queue(notify_hint(&[Control::Aim], "to aim"));
                     }
                     2 => {
-                        commands.queue(Notify {
-                            text_small: "Press [SHIFT] to sprint".into(),
-                            ..Default::default()
-                        });
+                        commands.queue(notify_hint(&[Control::Sprint], "to sprint"));
                     }
                     3 => {
-                        commands.queue(Notify {
-                            text_small: "Use mouse wheel to change zoom".into(),
-                            ..Default::default()
-                        });
+                        commands.queue(notify_hint(
+                            &[Control::ZoomIn, Control::ZoomOut],
+                            "to change zoom",
+                        ));
                     }
                     _ => {}
                 }
             }
 
-            let wave = f32::from(self.wave_number());
-            let wave_size = f32::from(self.wave().size);
+            let campaign = Arc::clone(&self.campaign);
+
+            let Some(wave) = self.wave(&campaign) else {
+                return;
+            };
 
-            if self
-                .rng
-                .random_bool(f32::min(BONUSES_PER_WAVE * wave / wave_size, 1.0).into())
-            {
+            let chance = f32::min(wave.bonuses / f32::from(wave.size), 1.0);
+
+            if self.rng.random_bool(chance.into()) {
                 commands.queue(BonusSpawn::new(event.position, self.wave_number()));
             }
         }
     }
 
     fn on_player_death(&mut self, _: &ActorDeathEvent, commands: &mut Commands) {
-        commands.queue(Notify {
-            text: "Game over".into(),
-            text_small: "You died. Press [ESC] to exit".into(),
-            duration: GAME_OVER_TEXT_DURATION,
-        });
+        commands.queue(check_game_over);
     }
 
     fn on_interval_update(&mut self, _time: Duration, commands: &mut Commands) -> Duration {
-        let timeout = self.task.get_timeout();
+        let timeout = self.task.get_timeout(self.wave(&self.campaign));
         self.task = WavesScenario::update(self, commands);
         return timeout;
     }
@@ -324,13 +363,6 @@ impl ScenarioLogic for WavesScenario {
     }
 }
 
-struct Wave {
-    size: u16,
-    pistol_chance: f64,
-    rifle_chance: f64,
-    agile_chance: f64,
-}
-
 struct SpawnActor {
     direction: f32,
     distance: f32,
@@ -379,11 +411,11 @@ impl Command for SpawnActor {
     }
 }
 
-fn count_zombies(world: &mut World) {
+fn count_enemies(world: &mut World) {
     if !world
         .query::<&Actor>()
         .iter(world)
-        .any(|a| a.config.kind == ActorKind::Zombie)
+        .any(|a| a.config.kind != ActorKind::Human)
     {
         if let Some(scenario) = world.resource_mut::<Scenario>().logic::<WavesScenario>() {
             scenario.task = Task::CompleteWave;
@@ -391,6 +423,51 @@ fn count_zombies(world: &mut World) {
     }
 }
 
+fn check_game_over(world: &mut World) {
+    let is_anyone_alive = world
+        .query_filtered::<&Health, With<Player>>()
+        .iter(world)
+        .any(Health::is_alive);
+
+    if is_anyone_alive {
+        Notify {
+            text_small: "Your partner died. Hold on till the end".into(),
+            ..Default::default()
+        }
+        .apply(world);
+    } else {
+        let settings = world.resource::<Settings>();
+        let exit = settings.controls.bindings.hint(Control::Exit, false);
+
+        Notify {
+            text: "Game over".into(),
+            text_small: format!("You died. Press {} to exit", exit).into(),
+            duration: GAME_OVER_TEXT_DURATION,
+        }
+        .apply(world);
+    }
+}
+
+/// Hints show the actual bindings, including gamepad ones when there's a gamepad to play with
+fn notify_hint(controls: &'static [Control], action: &'static str) -> impl Command {
+    return move |world: &mut World| {
+        let has_gamepad = world.query::<&Gamepad>().iter(world).next().is_some();
+        let settings = world.resource::<Settings>();
+        let with_gamepad = has_gamepad || settings.game.players > 1;
+        let hint = controls
+            .iter()
+            .map(|c| settings.controls.bindings.hint(*c, with_gamepad))
+            .collect::<Vec<_>>()
+            .join(" and ");
+
+        Notify {
+            text_small: format!("Press {} {}", hint, action).into(),
+            ..Default::default()
+        }
+        .apply(world);
+    };
+}
+
 fn heal_humans(world: &mut World) {
     for (actor, mut health) in world.query::<(&Actor, &mut Health)>().iter_mut(world) {
         if let ActorKind::Human = actor.config.kind {
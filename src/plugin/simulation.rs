@@ -0,0 +1,27 @@
+use bevy::{
+    app::{App, FixedUpdate, Plugin},
+    ecs::schedule::{SingleThreadedExecutor, SystemSet},
+    time::{Fixed, Time},
+};
+use std::time::Duration;
+
+/// Timestep of the game logic, see `AppExt::add_state_system_fixed`
+pub const TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Systems which change the game world on their own. A network client doesn't run them, since its
+/// world comes from the server
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuthoritySystems;
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Time::<Fixed>::from_duration(TIMESTEP));
+
+        // in parallel, systems might take the random numbers in a different order each time
+        app.edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor(SingleThreadedExecutor::new());
+        });
+    }
+}
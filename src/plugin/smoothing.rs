@@ -0,0 +1,112 @@
+use bevy::{
+    app::{App, FixedFirst, Plugin, RunFixedMainLoop, RunFixedMainLoopSystems},
+    ecs::{component::Component, schedule::IntoScheduleConfigs},
+    prelude::{Query, Res, Transform},
+    time::{Fixed, Time},
+};
+
+/// Shows entities moved by the fixed timestep smoothly at any frame rate
+pub struct SmoothingPlugin;
+
+impl Plugin for SmoothingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            RunFixedMainLoop,
+            (
+                restore.in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop),
+                interpolate.in_set(RunFixedMainLoopSystems::AfterFixedMainLoop),
+            ),
+        );
+        app.add_systems(FixedFirst, remember);
+    }
+}
+
+/// Between the ticks, the transform is shown interpolated from the previous tick to the last one,
+/// which lags a tick behind. It's put back before the next ticks, so the simulation never sees it
+#[derive(Component, Default)]
+pub struct Smoothing {
+    previous: Option<Transform>,
+    /// The transform of the last tick and the shown one
+    simulated: Option<(Transform, Transform)>,
+}
+
+impl Smoothing {
+    /// The transform of the last tick, even if an interpolated one is shown now
+    pub fn simulated(&self, transform: &Transform) -> Transform {
+        return self
+            .simulated
+            .map_or(*transform, |(simulated, _)| simulated);
+    }
+}
+
+fn restore(mut query: Query<(&mut Transform, &mut Smoothing)>) {
+    for (mut transform, mut smoothing) in query.iter_mut() {
+        let Some((simulated, shown)) = smoothing.simulated.take() else {
+            continue;
+        };
+
+        // unless something else has moved it since, like a network client does
+        if transform.translation == shown.translation {
+            transform.translation = simulated.translation;
+        }
+
+        if transform.rotation == shown.rotation {
+            transform.rotation = simulated.rotation;
+        }
+    }
+}
+
+fn remember(mut query: Query<(&Transform, &mut Smoothing)>) {
+    for (transform, mut smoothing) in query.iter_mut() {
+        smoothing.previous = Some(*transform);
+    }
+}
+
+fn interpolate(mut query: Query<(&mut Transform, &mut Smoothing)>, time: Res<Time<Fixed>>) {
+    let progress = time.overstep_fraction();
+
+    for (mut transform, mut smoothing) in query.iter_mut() {
+        let Some(previous) = smoothing.previous else {
+            continue;
+        };
+
+        let simulated = *transform;
+        transform.translation = previous.translation.lerp(simulated.translation, progress);
+        transform.rotation = previous.rotation.slerp(simulated.rotation, progress);
+        smoothing.simulated = Some((simulated, *transform));
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use bevy::{ecs::system::RunSystemOnce, math::Vec3, prelude::World};
+    use std::time::Duration;
+
+    #[test]
+    fn interpolate_and_restore() {
+        let mut world = World::new();
+        let mut time = Time::<Fixed>::from_seconds(1.0);
+        time.accumulate_overstep(Duration::from_millis(250));
+        world.insert_resource(time);
+
+        let entity = world
+            .spawn((Transform::default(), Smoothing::default()))
+            .id();
+
+        let position = |world: &World| world.get::<Transform>(entity).unwrap().translation;
+
+        // a tick moves it
+        world.run_system_once(remember).unwrap();
+        world
+            .entity_mut(entity)
+            .insert(Transform::from_xyz(4.0, 0.0, 0.0));
+
+        world.run_system_once(interpolate).unwrap();
+        assert_eq!(position(&world), Vec3::new(1.0, 0.0, 0.0));
+
+        world.run_system_once(restore).unwrap();
+        assert_eq!(position(&world), Vec3::new(4.0, 0.0, 0.0));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     data::PIXELS_PER_METER,
-    plugin::{Actor, Health, Weapon},
+    plugin::{Actor, AmmoReserve, Health, Weapon},
     resource::AssetStorage,
     state::AppState,
     util::ext::AppExt,
@@ -44,6 +44,9 @@ pub struct StatusBar {
     ammo: f32,
     #[uniform(0)]
     ammo_alpha: f32,
+    /// Spare ammo, full with the magazines which come along with a new weapon
+    #[uniform(0)]
+    reserve: f32,
     #[uniform(0)]
     stamina: f32,
 }
@@ -56,6 +59,7 @@ impl StatusBar {
             health_alpha: 0.0,
             ammo: 1.0,
             ammo_alpha: 0.0,
+            reserve: 0.0,
             stamina: 0.0,
         });
 
@@ -78,7 +82,13 @@ impl Material2d for StatusBar {
 }
 
 fn on_update(
-    targets: Query<(&Actor, &Health, Option<&Weapon>, &Children)>, // TODO: try to simplify
+    targets: Query<(
+        &Actor,
+        &Health,
+        Option<&Weapon>,
+        Option<&AmmoReserve>,
+        &Children,
+    )>, // TODO: try to simplify
     handles: Query<&MeshMaterial2d<StatusBar>>,
     mut assets: ResMut<Assets<StatusBar>>,
     time: Res<Time>,
@@ -86,7 +96,7 @@ fn on_update(
     let pulse = (time.elapsed_secs() * TAU / PULSE.as_secs_f32()).cos() / 2.0 + 0.5;
     let interpolation = f32::min(INTERPOLATION * time.delta().as_secs_f32(), 1.0);
 
-    for (actor, health, weapon, children) in targets.iter() {
+    for (actor, health, weapon, reserve, children) in targets.iter() {
         for child in children.iter() {
             if let Some(mut material) = handles.get(*child).ok().and_then(|h| assets.get_mut(h)) {
                 material.health -= (material.health - health.get()) * interpolation;
@@ -99,6 +109,12 @@ fn on_update(
 
                 if let Some(weapon) = weapon {
                     material.ammo = weapon.get_ammo_normalized(time.elapsed());
+                    material.reserve = reserve.map_or(1.0, |r| {
+                        let spare = r.get(weapon.config.projectile);
+                        let full = u16::from(weapon.config.ammo_capacity)
+                            * AmmoReserve::MAGAZINES_WITH_WEAPON;
+                        return f32::min(f32::from(spare) / f32::from(full.max(1)), 1.0);
+                    });
 
                     if weapon.is_reloading() {
                         material.ammo_alpha = pulse;
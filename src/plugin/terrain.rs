@@ -12,7 +12,6 @@ use bevy::{
     asset::{AssetServer, Assets},
     ecs::{
         component::Component,
-        schedule::IntoScheduleConfigs,
         system::{Command, Query},
         world::World,
     },
@@ -27,10 +26,7 @@ pub struct TerrainPlugin;
 
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(
-            AppState::Game,
-            on_update.after(crate::plugin::camera_target::on_update),
-        );
+        app.add_state_system(AppState::Game, on_update);
     }
 }
 
@@ -43,6 +43,13 @@ impl WeaponSet {
 
     fn remove_old_weapon_component(&self, world: &mut World) {
         if let Some(weapon) = world.get::<Weapon>(self.entity) {
+            if weapon.is_reloading() {
+                // the reloading is cancelled along with the weapon
+                world
+                    .resource::<AudioTracker>()
+                    .stop(self.entity, Weapon::SOUND_RELOADING);
+            }
+
             let weapon_mass = weapon.config.get_mass_with_full_ammo();
             self.update_actor_mass(world, -weapon_mass);
             world.entity_mut(self.entity).remove::<Weapon>();
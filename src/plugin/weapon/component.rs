@@ -1,5 +1,9 @@
-use crate::{plugin::WeaponConfig, util::ext::DurationExt};
+use crate::{
+    plugin::{AmmoReserve, FireMode, WeaponConfig, WeaponLoading},
+    util::ext::DurationExt,
+};
 use bevy::ecs::component::Component;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 const ARMING_DURATION: Duration = Duration::from_millis(150);
@@ -9,46 +13,145 @@ pub struct Weapon {
     pub config: &'static WeaponConfig,
     ammo: u8,
     reloading: Option<Duration>,
+    /// Shells are loaded one after another until it's full or interrupted
+    is_loading_shells: bool,
+    fire_mode: FireMode,
+    /// Shots fired since the trigger was pulled
+    trigger_shots: u8,
+    is_selector_held: bool,
     last_shot: Duration,
     next_time: Duration,
 }
 
+/// Weapon state apart from its config. The time is relative, so it can be restored at any moment
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponState {
+    pub ammo: u8,
+    pub reloading: Option<Duration>,
+    #[serde(default)]
+    pub is_loading_shells: bool,
+    pub fire_mode: FireMode,
+    pub ready_in: Duration,
+}
+
 impl Weapon {
     pub const BARREL_LENGTH: f32 = 0.6; // TODO: don't hardcode
+    pub const SOUND_RELOADING: &'static str = "sounds/reloading";
 
-    pub const fn new(config: &'static WeaponConfig) -> Self {
+    pub fn new(config: &'static WeaponConfig) -> Self {
         return Self {
             config,
             ammo: config.ammo_capacity,
             reloading: None,
+            is_loading_shells: false,
+            fire_mode: config.fire_modes.first().copied().unwrap_or(FireMode::Semi),
+            trigger_shots: 0,
+            is_selector_held: false,
             last_shot: Duration::ZERO,
             next_time: Duration::ZERO,
         };
     }
 
-    pub fn try_fire(&mut self, time: Duration) -> bool {
-        if self.is_ready(time) && self.has_ammo() {
+    /// Fires while the trigger is pulled, as many shots as the fire mode allows
+    pub fn try_fire(&mut self, time: Duration, is_trigger_pulled: bool) -> bool {
+        if !is_trigger_pulled {
+            self.trigger_shots = 0;
+            return false;
+        }
+
+        let is_limited = self
+            .fire_mode
+            .shots()
+            .is_some_and(|n| self.trigger_shots >= n);
+
+        if !is_limited && self.is_ready(time) && self.has_ammo() {
             self.ammo = self.ammo.saturating_sub(1);
+            self.trigger_shots = self.trigger_shots.saturating_add(1);
             self.last_shot = time;
-            self.next_time = time + Duration::from_secs_f32(60.0 / self.config.fire_rate);
+            self.next_time = time + self.fire_interval();
             return true;
         } else {
             return false;
         }
     }
 
+    /// Switches to the next fire mode once the selector is pressed
+    pub fn update_selector(&mut self, is_held: bool) {
+        if is_held && !self.is_selector_held {
+            let modes = &self.config.fire_modes;
+            let i = modes.iter().position(|m| *m == self.fire_mode).unwrap_or(0);
+            self.fire_mode = modes
+                .get((i + 1) % modes.len())
+                .copied()
+                .unwrap_or(self.fire_mode);
+            self.trigger_shots = 0;
+        }
+
+        self.is_selector_held = is_held;
+    }
+
+    /// Switches to the fire mode at once, if the weapon has it
+    pub fn set_fire_mode(&mut self, fire_mode: FireMode) {
+        if self.fire_mode != fire_mode && self.config.fire_modes.contains(&fire_mode) {
+            self.fire_mode = fire_mode;
+            self.trigger_shots = 0;
+        }
+    }
+
+    /// Starts reloading, which takes the duration. A weapon loaded by shells takes it per shell
     pub fn reload(&mut self, time: Duration, duration: Duration) {
         if self.reloading.is_none() {
             self.reloading = Some(duration);
             self.next_time = time + duration;
+            self.is_loading_shells = matches!(self.config.loading, WeaponLoading::Shells);
         }
     }
 
-    pub fn complete_reloading(&mut self, time: Duration) {
+    /// Stops loading shells, so the loaded ones can be fired at once. Returns whether it stopped
+    pub fn interrupt_reloading(&mut self) -> bool {
+        if self.is_loading_shells && self.ammo > 0 {
+            self.is_loading_shells = false;
+
+            if self.reloading.take().is_some() {
+                self.next_time = self.last_shot + self.fire_interval();
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    /// Whether there is room for ammo and the reserve has some, if the actor has any
+    pub fn can_reload(&self, reserve: Option<&AmmoReserve>) -> bool {
+        return self.ammo < self.config.ammo_capacity
+            && reserve.is_none_or(|r| r.get(self.config.projectile) > 0);
+    }
+
+    pub fn complete_reloading(&mut self, time: Duration, reserve: Option<&mut AmmoReserve>) {
         if self.reloading.is_some() {
             let was_armed = self.is_armed();
             self.reloading = None;
-            self.ammo = self.config.ammo_capacity;
+
+            let capacity = self.config.ammo_capacity;
+
+            let (loaded, room) = match self.config.loading {
+                WeaponLoading::Magazine => (0, capacity), // the partial magazine is thrown away
+                WeaponLoading::Shells => {
+                    (self.ammo, u8::min(capacity.saturating_sub(self.ammo), 1))
+                }
+            };
+
+            let (taken, has_more) = match reserve {
+                Some(reserve) => {
+                    let taken = reserve.take(self.config.projectile, room);
+                    (taken, reserve.get(self.config.projectile) > 0)
+                }
+                None => (room, true),
+            };
+
+            self.ammo = loaded + taken;
+            self.is_loading_shells &= has_more && self.ammo < capacity;
 
             if !was_armed {
                 self.next_time = time + ARMING_DURATION;
@@ -56,6 +159,32 @@ impl Weapon {
         }
     }
 
+    pub fn get_state(&self, time: Duration) -> WeaponState {
+        return WeaponState {
+            ammo: self.ammo,
+            reloading: self.reloading,
+            is_loading_shells: self.is_loading_shells,
+            fire_mode: self.fire_mode,
+            ready_in: self.next_time.saturating_sub(time),
+        };
+    }
+
+    pub fn set_state(&mut self, state: &WeaponState, time: Duration) {
+        self.ammo = state.ammo;
+        self.reloading = state.reloading;
+        self.is_loading_shells = state.is_loading_shells;
+        self.next_time = time + state.ready_in;
+
+        if self.config.fire_modes.contains(&state.fire_mode) {
+            self.fire_mode = state.fire_mode;
+        }
+    }
+
+    /// Loads as much as fits
+    pub fn set_ammo(&mut self, ammo: u8) {
+        self.ammo = u8::min(ammo, self.config.ammo_capacity);
+    }
+
     pub fn get_mass(&self) -> f32 {
         return self.config.mass + self.config.projectile.mass * f32::from(self.ammo);
     }
@@ -76,12 +205,22 @@ impl Weapon {
                 self.next_time,
             );
 
+            if self.is_loading_shells {
+                // the shell being loaded fills its share
+                let shells = f32::from(self.ammo) + progress;
+                return shells / f32::from(self.config.ammo_capacity.max(1));
+            }
+
             return progress;
         } else {
             return self.config.get_ammo_normalized(self.ammo);
         }
     }
 
+    pub fn ammo(&self) -> u8 {
+        return self.ammo;
+    }
+
     pub fn has_ammo(&self) -> bool {
         return self.ammo > 0 || self.config.ammo_capacity == 0;
     }
@@ -94,7 +233,120 @@ impl Weapon {
         return self.reloading.is_some();
     }
 
+    pub fn fire_mode(&self) -> FireMode {
+        return self.fire_mode;
+    }
+
+    /// Whether the next shell is going to be loaded after the current one
+    pub fn is_loading_shells(&self) -> bool {
+        return self.is_loading_shells;
+    }
+
     pub fn is_ready(&self, time: Duration) -> bool {
         return self.next_time < time;
     }
+
+    fn fire_interval(&self) -> Duration {
+        return Duration::from_secs_f32(60.0 / self.config.fire_rate);
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::plugin::WeaponRegistry;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn load_shells() {
+        let registry = WeaponRegistry::load().unwrap();
+        let shotgun = registry.get("Saiga-12K").unwrap();
+
+        let mut reserve = AmmoReserve::default();
+        reserve.add(shotgun.projectile, 2);
+
+        let mut weapon = Weapon::new(shotgun);
+        weapon.set_ammo(8);
+        assert!(weapon.can_reload(Some(&reserve)));
+
+        weapon.reload(SECOND, SECOND);
+        weapon.complete_reloading(SECOND * 2, Some(&mut reserve));
+        assert_eq!(weapon.ammo(), 9);
+        assert!(weapon.is_loading_shells());
+
+        // firing interrupts the next shell
+        weapon.reload(SECOND * 2, SECOND);
+        assert!(!weapon.is_ready(SECOND * 2));
+        assert!(weapon.interrupt_reloading());
+        assert!(weapon.try_fire(SECOND * 2, true));
+        assert_eq!(weapon.ammo(), 8);
+        assert_eq!(reserve.get(shotgun.projectile), 1);
+        assert!(!weapon.is_loading_shells());
+    }
+
+    #[test]
+    fn fire_modes() {
+        let registry = WeaponRegistry::load().unwrap();
+        let rifle = registry.get("AK-74M").unwrap();
+
+        // no real weapon of the game has it
+        let burst = toml::from_str::<WeaponConfig>(
+            r#"
+            level = 0
+            mass = 3.0
+            muzzle_velocity = 900.0
+            deviation = 0.0
+            fire_rate = 600.0
+            fire_modes = ["burst"]
+            projectile = "5.45x39"
+            ammo_capacity = 30
+            reloading_time = 1.0
+            loading = "magazine"
+            has_bolt = true
+            is_shotgun = false
+            grip = "two_hands"
+            image_offset = 0.0
+            "#,
+        )
+        .unwrap();
+
+        let mut weapon = Weapon::new(rifle);
+        let shots = |weapon: &mut Weapon, round: u32| {
+            let start = SECOND * round * 100;
+            let mut shots = 0;
+
+            for i in 1..=10 {
+                if weapon.try_fire(start + SECOND * i, true) {
+                    shots += 1;
+                }
+            }
+
+            weapon.try_fire(start + SECOND * 11, false);
+            return shots;
+        };
+
+        assert_eq!(weapon.fire_mode(), FireMode::Auto);
+        assert_eq!(shots(&mut weapon, 1), 10);
+
+        // a held selector switches once
+        weapon.update_selector(true);
+        weapon.update_selector(true);
+        assert_eq!(weapon.fire_mode(), FireMode::Semi);
+        assert_eq!(shots(&mut weapon, 2), 1);
+
+        weapon.update_selector(false);
+        weapon.update_selector(true);
+        assert_eq!(weapon.fire_mode(), FireMode::Auto);
+
+        // only modes the weapon has can be set
+        weapon.set_fire_mode(FireMode::Burst);
+        assert_eq!(weapon.fire_mode(), FireMode::Auto);
+        weapon.set_fire_mode(FireMode::Semi);
+        assert_eq!(weapon.fire_mode(), FireMode::Semi);
+
+        let mut weapon = Weapon::new(Box::leak(Box::new(burst)));
+        assert_eq!(shots(&mut weapon, 3), FireMode::BURST_SHOTS);
+    }
 }
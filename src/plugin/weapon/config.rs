@@ -1,18 +1,36 @@
-use crate::{plugin::ProjectileConfig, util::ext::RngExt2};
+use crate::{
+    plugin::ProjectileConfig,
+    util::{ext::RngExt2, serde_secs},
+};
+use anyhow::{ensure, Result};
 use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct WeaponConfig {
-    pub name: &'static str,
+    /// Taken from the name of the directory the config is located in
+    #[serde(skip)]
+    pub name: String,
+    /// Name of another weapon which images and sounds to use instead of own ones
+    #[serde(default)]
+    pub assets: Option<String>,
+    /// Zero level weapons are never given to the player
     pub level: u8,
     pub mass: f32,
     pub muzzle_velocity: f32,
     pub deviation: f32,
     pub fire_rate: f32,
-    pub is_automatic: bool,
+    /// The first one is selected on a new weapon
+    pub fire_modes: Vec<FireMode>,
+    #[serde(deserialize_with = "ProjectileConfig::deserialize_by_name")]
     pub projectile: &'static ProjectileConfig,
     pub ammo_capacity: u8,
+    /// Of one shell, for a weapon loaded by shells
+    #[serde(with = "serde_secs")]
     pub reloading_time: Duration,
+    pub loading: WeaponLoading,
     pub has_bolt: bool,
     pub is_shotgun: bool,
     pub grip: WeaponGrip,
@@ -22,247 +40,53 @@ pub struct WeaponConfig {
 impl WeaponConfig {
     pub const VELOCITY_DEVIATION: f32 = 0.06;
 
-    pub const RELOADING_TIME_PISTOL: Duration = Duration::from_millis(800);
-    pub const RELOADING_TIME_SHOTGUN_LIGHT: Duration = Duration::from_millis(1200);
-    pub const RELOADING_TIME_SHOTGUN: Duration = Duration::from_millis(1400);
-    pub const RELOADING_TIME_SMG: Duration = Duration::from_millis(1000);
-    pub const RELOADING_TIME_CARBINE: Duration = Duration::from_millis(1200);
-    pub const RELOADING_TIME_RIFLE: Duration = Duration::from_millis(1400);
-    pub const RELOADING_TIME_RIFLE_HEAVY: Duration = Duration::from_millis(1600);
-    pub const RELOADING_TIME_MACHINE_GUN: Duration = Duration::from_millis(5000);
-
     pub const RECOIL_MASS_POW: f32 = 0.25;
     pub const RECOIL_POW: f32 = 0.5;
     pub const RECOIL_MUL: f32 = 13.0;
 
-    pub const ALL: &'static [Self] = &[
-        Self::PM,
-        Self::TT,
-        Self::IZH_43_SAWED_OFF,
-        Self::PP_91_KEDR,
-        Self::IZH_27,
-        Self::PP_19_BIZON,
-        Self::AKS_74U,
-        Self::AK_74M,
-        Self::RPK_74,
-        Self::RPG_7,
-        Self::SAIGA_12K,
-        Self::PKM,
-    ];
-
-    pub const PM: Self = Self {
-        name: "PM",
-        level: 1,
-        mass: 0.73,
-        muzzle_velocity: 315.0,
-        deviation: 0.03,
-        fire_rate: 120.0,
-        is_automatic: false,
-        projectile: &ProjectileConfig::_9X18,
-        ammo_capacity: 8,
-        reloading_time: Self::RELOADING_TIME_PISTOL,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::OneHand,
-        image_offset: 2.0,
-    };
-
-    pub const PM_BROKEN: Self = Self {
-        muzzle_velocity: 280.0,
-        ..Self::PM
-    };
-
-    pub const TT: Self = Self {
-        name: "TT",
-        level: 1,
-        mass: 0.85,
-        muzzle_velocity: 430.0,
-        deviation: 0.025,
-        fire_rate: 110.0,
-        is_automatic: false,
-        projectile: &ProjectileConfig::_7_62X25,
-        ammo_capacity: 8,
-        reloading_time: Self::RELOADING_TIME_PISTOL,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::OneHand,
-        image_offset: 2.0,
-    };
-
-    pub const IZH_43_SAWED_OFF: Self = Self {
-        name: "IZh-43 sawed-off",
-        level: 2,
-        mass: 2.2,
-        muzzle_velocity: 260.0,
-        deviation: 0.1,
-        fire_rate: 100.0,
-        is_automatic: false,
-        projectile: &ProjectileConfig::_12X76,
-        ammo_capacity: 2,
-        reloading_time: Self::RELOADING_TIME_SHOTGUN_LIGHT,
-        has_bolt: false,
-        is_shotgun: true,
-        grip: WeaponGrip::TwoHands,
-        image_offset: 3.5,
-    };
-
-    pub const PP_91_KEDR: Self = Self {
-        name: "PP-91 Kedr",
-        level: 2,
-        mass: 1.6,
-        muzzle_velocity: 310.0,
-        deviation: 0.035,
-        fire_rate: 900.0,
-        is_automatic: true,
-        projectile: &ProjectileConfig::_9X18,
-        ammo_capacity: 20,
-        reloading_time: Self::RELOADING_TIME_SMG,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHands,
-        image_offset: 3.5,
-    };
-
-    pub const IZH_27: Self = Self {
-        name: "IZh-27",
-        level: 3,
-        mass: 3.2,
-        muzzle_velocity: 410.0,
-        deviation: 0.022,
-        fire_rate: 85.0,
-        is_automatic: false,
-        projectile: &ProjectileConfig::_12X76,
-        ammo_capacity: 2,
-        reloading_time: Self::RELOADING_TIME_SHOTGUN,
-        has_bolt: false,
-        is_shotgun: true,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 10.0,
-    };
-
-    pub const PP_19_BIZON: Self = Self {
-        name: "PP-19 Bizon",
-        level: 3,
-        mass: 2.7,
-        muzzle_velocity: 330.0,
-        deviation: 0.025,
-        fire_rate: 680.0,
-        is_automatic: true,
-        projectile: &ProjectileConfig::_9X18,
-        ammo_capacity: 64,
-        reloading_time: Self::RELOADING_TIME_SMG,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 7.0,
-    };
-
-    pub const AKS_74U: Self = Self {
-        name: "AKS-74U",
-        level: 4,
-        mass: 2.9,
-        muzzle_velocity: 735.0,
-        deviation: 0.02,
-        fire_rate: 675.0,
-        is_automatic: true,
-        projectile: &ProjectileConfig::_5_45X39,
-        ammo_capacity: 30,
-        reloading_time: Self::RELOADING_TIME_CARBINE,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 8.0,
-    };
-
-    pub const AKS_74U_BROKEN: Self = Self {
-        muzzle_velocity: 500.0,
-        ..Self::AKS_74U
-    };
-
-    pub const AK_74M: Self = Self {
-        name: "AK-74M",
-        level: 4,
-        mass: 3.83,
-        muzzle_velocity: 910.0,
-        deviation: 0.015,
-        fire_rate: 600.0,
-        is_automatic: true,
-        projectile: &ProjectileConfig::_5_45X39,
-        ammo_capacity: 30,
-        reloading_time: Self::RELOADING_TIME_RIFLE,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 9.0,
-    };
-
-    pub const RPK_74: Self = Self {
-        name: "RPK-74",
-        level: 5,
-        mass: 5.24,
-        muzzle_velocity: 960.0,
-        deviation: 0.012,
-        fire_rate: 600.0,
-        is_automatic: true,
-        projectile: &ProjectileConfig::_5_45X39,
-        ammo_capacity: 45,
-        reloading_time: Self::RELOADING_TIME_RIFLE_HEAVY,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 9.0,
-    };
-
-    pub const RPG_7: Self = Self {
-        name: "RPG-7",
-        level: 5,
-        mass: 5.8,
-        muzzle_velocity: 120.0,
-        deviation: 0.0, // make perfect precision since each shoot from RPG-7 takes a very long time
-        fire_rate: 120.0,
-        is_automatic: false,
-        projectile: &ProjectileConfig::TBG_7V,
-        ammo_capacity: 1,
-        reloading_time: Duration::from_secs(1),
-        has_bolt: false,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 21.0,
-    };
-
-    pub const SAIGA_12K: Self = Self {
-        name: "Saiga-12K",
-        level: 6,
-        mass: 3.3,
-        muzzle_velocity: 410.0,
-        deviation: 0.03,
-        fire_rate: 220.0,
-        is_automatic: false,
-        projectile: &ProjectileConfig::_12X76,
-        ammo_capacity: 10,
-        reloading_time: Self::RELOADING_TIME_RIFLE,
-        has_bolt: true,
-        is_shotgun: true,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 9.0,
-    };
-
-    pub const PKM: Self = Self {
-        name: "PKM",
-        level: 7,
-        mass: 8.2,
-        muzzle_velocity: 825.0,
-        deviation: 0.011,
-        fire_rate: 650.0,
-        is_automatic: true,
-        projectile: &ProjectileConfig::_7_62X54,
-        ammo_capacity: 100,
-        reloading_time: Self::RELOADING_TIME_MACHINE_GUN,
-        has_bolt: true,
-        is_shotgun: false,
-        grip: WeaponGrip::TwoHandsWithButt,
-        image_offset: 10.0,
-    };
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.mass > 0.0 && self.mass.is_finite(),
+            "Mass must be a finite positive number",
+        );
+        ensure!(
+            self.muzzle_velocity > ProjectileConfig::VELOCITY_MIN,
+            "Muzzle velocity must be greater than {}",
+            ProjectileConfig::VELOCITY_MIN,
+        );
+        ensure!(
+            self.deviation >= 0.0 && self.deviation.is_finite(),
+            "Deviation must be a finite non-negative number",
+        );
+        ensure!(
+            self.fire_rate > 0.0 && self.fire_rate.is_finite(),
+            "Fire rate must be a finite positive number",
+        );
+        ensure!(
+            !self.fire_modes.is_empty(),
+            "At least one fire mode must be given",
+        );
+        ensure!(
+            self.fire_modes
+                .iter()
+                .enumerate()
+                .all(|(i, m)| !self.fire_modes[..i].contains(m)),
+            "Fire modes must not repeat",
+        );
+        ensure!(
+            self.ammo_capacity > 0,
+            "Ammo capacity must be greater than zero"
+        );
+        ensure!(
+            !self.reloading_time.is_zero(),
+            "Reloading time must be greater than zero",
+        );
+        ensure!(
+            self.image_offset.is_finite(),
+            "Image offset must be a finite number",
+        );
+        return Ok(());
+    }
 
     pub fn generate_deviation(&self, rng: &mut Pcg32) -> f32 {
         return rng.gen_normal(self.deviation);
@@ -277,8 +101,12 @@ impl WeaponConfig {
         return self.mass + self.projectile.mass * f32::from(self.ammo_capacity);
     }
 
+    pub fn get_assets_path(&self) -> String {
+        return format!("weapons/{}", self.assets.as_ref().unwrap_or(&self.name));
+    }
+
     pub fn get_image_path(&self) -> String {
-        return format!("weapons/{}/image.png", self.name);
+        return format!("{}/image.png", self.get_assets_path());
     }
 
     pub fn get_ammo_normalized(&self, ammo: u8) -> f32 {
@@ -290,6 +118,54 @@ impl WeaponConfig {
     }
 }
 
+/// How many shots a weapon fires while its trigger is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FireMode {
+    Semi,
+    Burst,
+    Auto,
+}
+
+impl FireMode {
+    pub const BURST_SHOTS: u8 = 3;
+
+    /// None if unlimited
+    pub fn shots(self) -> Option<u8> {
+        return match self {
+            Self::Semi => Some(1),
+            Self::Burst => Some(Self::BURST_SHOTS),
+            Self::Auto => None,
+        };
+    }
+
+    /// Of the given modes, the one which fires the number of shots closest to this one. On a tie
+    /// the one with fewer shots wins
+    pub fn closest_of(self, modes: &[Self]) -> Option<Self> {
+        let shots = self.shots_or_max();
+        return modes
+            .iter()
+            .min_by_key(|m| (m.shots_or_max().abs_diff(shots), m.shots_or_max()))
+            .copied();
+    }
+
+    fn shots_or_max(self) -> u16 {
+        return self.shots().map_or(u16::MAX, u16::from);
+    }
+}
+
+/// How a weapon takes ammo from the reserve on reloading
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeaponLoading {
+    /// A fresh magazine replaces the current one along with the rounds left in it
+    Magazine,
+    /// Shells are added until the weapon is full
+    Shells,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WeaponGrip {
     OneHand,
     TwoHands,
@@ -313,3 +189,19 @@ impl WeaponGrip {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_fire_mode() {
+        use FireMode::{Auto, Burst, Semi};
+
+        assert_eq!(Auto.closest_of(&[Semi, Burst]), Some(Burst));
+        assert_eq!(Semi.closest_of(&[Auto, Burst]), Some(Burst));
+        assert_eq!(Burst.closest_of(&[Auto, Semi]), Some(Semi));
+        assert_eq!(Burst.closest_of(&[Burst, Semi]), Some(Burst));
+        assert_eq!(Auto.closest_of(&[]), None);
+    }
+}
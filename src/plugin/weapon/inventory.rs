@@ -0,0 +1,351 @@
+use crate::plugin::{
+    AmmoReserve, BonusPickUp, BonusSpawn, Weapon, WeaponConfig, WeaponGrip, WeaponSet,
+};
+use bevy::{
+    ecs::{component::Component, entity::Entity, system::Command},
+    math::Vec3Swizzles,
+    prelude::{Transform, World},
+};
+use serde::{Deserialize, Serialize};
+
+/// Where a weapon is carried. Only one weapon fits a slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InventorySlot {
+    Primary,
+    Secondary,
+    /// Free hands, which are always available
+    Melee,
+}
+
+impl InventorySlot {
+    const ALL: [Self; 3] = [Self::Primary, Self::Secondary, Self::Melee];
+
+    pub fn of(weapon: &WeaponConfig) -> Self {
+        return match weapon.grip {
+            WeaponGrip::OneHand => Self::Secondary,
+            WeaponGrip::TwoHands | WeaponGrip::TwoHandsWithButt => Self::Primary,
+        };
+    }
+
+    fn held_by(weapon: Option<&Weapon>) -> Self {
+        return weapon.map_or(Self::Melee, |w| Self::of(w.config));
+    }
+}
+
+/// What a player does with its inventory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryCommand {
+    Select(InventorySlot),
+    Next,
+    Previous,
+    /// Drops the weapon in hands and takes the next one
+    Drop,
+    /// Picks up the weapon underfoot, even if it replaces a carried one
+    PickUp,
+}
+
+impl InventoryCommand {
+    pub const ALL: [Self; 7] = [
+        Self::Select(InventorySlot::Primary),
+        Self::Select(InventorySlot::Secondary),
+        Self::Select(InventorySlot::Melee),
+        Self::Next,
+        Self::Previous,
+        Self::Drop,
+        Self::PickUp,
+    ];
+}
+
+/// Weapons which an actor carries besides the one in its hands, with the ammo left in them
+#[derive(Component, Default)]
+pub struct Inventory {
+    stored: Vec<(&'static WeaponConfig, u8)>,
+}
+
+impl Inventory {
+    pub fn stored(&self) -> &[(&'static WeaponConfig, u8)] {
+        return &self.stored;
+    }
+
+    /// Puts the weapon to its slot. Returns the one which was there before
+    pub fn put(
+        &mut self,
+        weapon: &'static WeaponConfig,
+        ammo: u8,
+    ) -> Option<(&'static WeaponConfig, u8)> {
+        let replaced = self.take(InventorySlot::of(weapon));
+        self.stored.push((weapon, ammo));
+        return replaced;
+    }
+
+    fn take(&mut self, slot: InventorySlot) -> Option<(&'static WeaponConfig, u8)> {
+        let i = self
+            .stored
+            .iter()
+            .position(|(w, _)| InventorySlot::of(w) == slot)?;
+
+        return Some(self.stored.remove(i));
+    }
+
+    pub fn carries(&self, weapon: &WeaponConfig) -> bool {
+        return self.stored.iter().any(|(w, _)| std::ptr::eq(*w, weapon));
+    }
+
+    /// Whether the weapon can be picked up without replacing a carried one, so it either goes to
+    /// an empty slot or only tops up the ammo
+    pub fn fits(&self, held: Option<&Weapon>, weapon: &WeaponConfig) -> bool {
+        let slot = InventorySlot::of(weapon);
+        return held.is_some_and(|w| std::ptr::eq(w.config, weapon))
+            || self.carries(weapon)
+            || (InventorySlot::held_by(held) != slot && !self.has(slot));
+    }
+
+    fn has(&self, slot: InventorySlot) -> bool {
+        return slot == InventorySlot::Melee
+            || self
+                .stored
+                .iter()
+                .any(|(w, _)| InventorySlot::of(w) == slot);
+    }
+
+    /// The slot next to the held one which isn't empty, going around
+    fn cycle(&self, held: InventorySlot, is_forward: bool) -> InventorySlot {
+        let all = InventorySlot::ALL;
+        let held_i = all.iter().position(|s| *s == held).unwrap_or(0);
+
+        for step in 1..all.len() {
+            let i = if is_forward {
+                (held_i + step) % all.len()
+            } else {
+                (held_i + all.len() - step) % all.len()
+            };
+
+            if self.has(all[i]) {
+                return all[i];
+            }
+        }
+
+        return held;
+    }
+}
+
+/// Carries out an inventory command of an actor
+pub struct InventoryUse {
+    pub entity: Entity,
+    pub command: InventoryCommand,
+}
+
+impl Command for InventoryUse {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        let held = InventorySlot::held_by(world.get::<Weapon>(self.entity));
+
+        let Some(inventory) = world.get::<Inventory>(self.entity) else {
+            return;
+        };
+
+        let slot = match self.command {
+            InventoryCommand::Select(slot) => slot,
+            InventoryCommand::Next => inventory.cycle(held, true),
+            InventoryCommand::Previous => inventory.cycle(held, false),
+            InventoryCommand::Drop => {
+                if held == InventorySlot::Melee {
+                    return;
+                }
+
+                drop_held(world, self.entity);
+
+                // take the next weapon, unless there's none
+                let Some(inventory) = world.get::<Inventory>(self.entity) else {
+                    return;
+                };
+
+                inventory.cycle(InventorySlot::Melee, true)
+            }
+            InventoryCommand::PickUp => {
+                BonusPickUp {
+                    entity: self.entity,
+                }
+                .apply(world);
+
+                return;
+            }
+        };
+
+        select(world, self.entity, slot);
+    }
+}
+
+/// Gives a picked up weapon to an actor. One with an inventory stores it, and drops the weapon
+/// which was in the slot before. Otherwise the weapon in hands is dropped and replaced. An actor
+/// with an ammo reserve takes only the ammo of a weapon it already carries
+pub struct WeaponPickUp {
+    pub entity: Entity,
+    pub weapon: &'static WeaponConfig,
+    /// Loaded in the weapon
+    pub ammo: u8,
+    /// Spare ammo which comes along
+    pub reserve: u16,
+}
+
+impl Command for WeaponPickUp {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        let is_carried = world
+            .get::<Weapon>(self.entity)
+            .is_some_and(|w| std::ptr::eq(w.config, self.weapon))
+            || world
+                .get::<Inventory>(self.entity)
+                .is_some_and(|i| i.carries(self.weapon));
+
+        if let Some(mut reserve) = world.get_mut::<AmmoReserve>(self.entity) {
+            let projectile = self.weapon.projectile;
+            reserve.add(projectile, self.reserve);
+
+            if is_carried {
+                reserve.add(projectile, u16::from(self.ammo));
+                return;
+            }
+        }
+
+        let held = InventorySlot::held_by(world.get::<Weapon>(self.entity));
+        let slot = InventorySlot::of(self.weapon);
+        let picked = Some((self.weapon, self.ammo));
+
+        let Some(mut inventory) = world.get_mut::<Inventory>(self.entity) else {
+            drop_held(world, self.entity);
+            equip(world, self.entity, picked);
+            return;
+        };
+
+        if held == slot {
+            drop_held(world, self.entity);
+            equip(world, self.entity, picked);
+            return;
+        }
+
+        if let Some((replaced, ammo)) = inventory.put(self.weapon, self.ammo) {
+            drop_weapon(world, self.entity, replaced, ammo);
+        }
+
+        if held == InventorySlot::Melee {
+            select(world, self.entity, slot);
+        }
+    }
+}
+
+/// Takes the weapon of the slot in hands, and puts the held one to the inventory
+fn select(world: &mut World, entity: Entity, slot: InventorySlot) {
+    let held = world.get::<Weapon>(entity).map(|w| (w.config, w.ammo()));
+
+    if held.map_or(InventorySlot::Melee, |(w, _)| InventorySlot::of(w)) == slot {
+        return;
+    }
+
+    let Some(mut inventory) = world.get_mut::<Inventory>(entity) else {
+        return;
+    };
+
+    let taken = inventory.take(slot);
+
+    if taken.is_none() && slot != InventorySlot::Melee {
+        return; // the slot is empty
+    }
+
+    if let Some((weapon, ammo)) = held {
+        inventory.put(weapon, ammo);
+    }
+
+    equip(world, entity, taken);
+}
+
+fn equip(world: &mut World, entity: Entity, weapon: Option<(&'static WeaponConfig, u8)>) {
+    WeaponSet {
+        entity,
+        weapon: weapon.map(|(w, _)| w),
+    }
+    .apply(world);
+
+    if let (Some((_, ammo)), Some(mut weapon)) = (weapon, world.get_mut::<Weapon>(entity)) {
+        weapon.set_ammo(ammo);
+    }
+}
+
+fn drop_held(world: &mut World, entity: Entity) {
+    let Some((weapon, ammo)) = world.get::<Weapon>(entity).map(|w| (w.config, w.ammo())) else {
+        return;
+    };
+
+    WeaponSet {
+        entity,
+        weapon: None,
+    }
+    .apply(world);
+
+    drop_weapon(world, entity, weapon, ammo);
+}
+
+fn drop_weapon(world: &mut World, entity: Entity, weapon: &'static WeaponConfig, ammo: u8) {
+    let Some(position) = world.get::<Transform>(entity).map(|t| t.translation.xy()) else {
+        return;
+    };
+
+    BonusSpawn::dropped(position, weapon, ammo)
+        .dropped_by(entity)
+        .apply(world);
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+    use crate::plugin::WeaponRegistry;
+
+    #[test]
+    fn put_and_cycle() {
+        let registry = WeaponRegistry::load().unwrap();
+        let pm = registry.get("PM").unwrap();
+        let tt = registry.get("TT").unwrap();
+        let ak = registry.get("AK-74M").unwrap();
+
+        let mut inventory = Inventory::default();
+        let held = InventorySlot::Melee;
+        assert_eq!(inventory.cycle(held, true), InventorySlot::Melee);
+
+        assert!(inventory.put(pm, 3).is_none());
+        assert_eq!(inventory.cycle(held, true), InventorySlot::Secondary);
+        assert_eq!(inventory.cycle(held, false), InventorySlot::Secondary);
+
+        assert!(inventory.put(ak, 30).is_none());
+        assert_eq!(inventory.cycle(held, true), InventorySlot::Primary);
+        assert_eq!(inventory.cycle(held, false), InventorySlot::Secondary);
+
+        let (replaced, ammo) = inventory.put(tt, 8).unwrap();
+
+        assert!(std::ptr::eq(replaced, pm));
+        assert_eq!(ammo, 3);
+        assert_eq!(inventory.stored().len(), 2);
+    }
+
+    #[test]
+    fn fits() {
+        let registry = WeaponRegistry::load().unwrap();
+        let pm = registry.get("PM").unwrap();
+        let tt = registry.get("TT").unwrap();
+        let ak = registry.get("AK-74M").unwrap();
+
+        let mut inventory = Inventory::default();
+        let held = Weapon::new(pm);
+        assert!(inventory.fits(Some(&held), pm));
+        assert!(!inventory.fits(Some(&held), tt));
+        assert!(inventory.fits(Some(&held), ak));
+        assert!(inventory.fits(None, tt));
+
+        inventory.put(ak, 30);
+        assert!(inventory.fits(Some(&held), ak));
+        assert!(!inventory.fits(Some(&held), registry.get("PKM").unwrap()));
+    }
+}
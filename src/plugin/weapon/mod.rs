@@ -1,60 +1,65 @@
 mod command;
 mod component;
 mod config;
+mod inventory;
+mod registry;
+mod reserve;
 
-pub use self::{command::*, component::*, config::*};
+pub use self::{command::*, component::*, config::*, inventory::*, registry::*, reserve::*};
 use crate::{
     plugin::{
-        collision::CollisionSystems, Actor, ActorActionsExt, AudioPlay, AudioTracker,
+        collision::CollisionSystems, Actor, ActorActionsExt, AudioPlay, AudioTracker, Noise,
         ProjectilePhysics, ProjectileSpawn, ShellParticleSpawn,
     },
-    resource::HitResource,
+    resource::{HitResource, RngResource},
     state::AppState,
     util::ext::{AppExt, QuatExt, Vec2Ext},
 };
 use bevy::{
-    ecs::system::{Deferred, Local, Query},
+    ecs::{
+        message::MessageWriter,
+        system::{Deferred, Query},
+    },
     math::{Vec2, Vec3Swizzles},
-    prelude::{App, Commands, Entity, IntoScheduleConfigs, Plugin, Res, Time, Transform},
+    prelude::{App, Commands, Entity, IntoScheduleConfigs, Plugin, Res, ResMut, Time, Transform},
 };
-use rand::{RngExt, SeedableRng};
-use rand_pcg::Pcg32;
+use rand::RngExt;
 
 pub struct WeaponPlugin;
 
 impl Plugin for WeaponPlugin {
     fn build(&self, app: &mut App) {
-        app.add_state_system(AppState::Game, on_update.after(CollisionSystems));
-    }
-}
+        let registry = WeaponRegistry::load().unwrap_or_else(|error| {
+            panic!("Failed to load weapons: {:?}", error);
+        });
 
-struct Data {
-    rng: Pcg32,
-}
-
-impl Default for Data {
-    fn default() -> Self {
-        return Self {
-            rng: Pcg32::seed_from_u64(0),
-        };
+        app.insert_resource(registry);
+        app.add_state_system_fixed(AppState::Game, on_update.after(CollisionSystems));
     }
 }
 
 fn on_update(
-    mut data: Local<Data>,
-    mut query: Query<(Entity, &Actor, &Transform, &mut Weapon)>,
+    mut query: Query<(
+        Entity,
+        &Actor,
+        &Transform,
+        &mut Weapon,
+        Option<&mut AmmoReserve>,
+    )>,
     mut commands: Commands,
     mut hits: Deferred<HitResource>,
+    mut noises: MessageWriter<Noise>,
     audio: Res<AudioTracker>,
     time: Res<Time>,
+    mut rng: ResMut<RngResource>,
 ) {
     crate::util::bench::bench!();
     let now = time.elapsed();
 
-    for (entity, actor, transform, mut weapon) in query.iter_mut() {
+    for (entity, actor, transform, mut weapon, mut reserve) in query.iter_mut() {
         if weapon.is_reloading() && weapon.is_ready(now) {
             let was_armed = weapon.is_armed();
-            weapon.complete_reloading(now);
+            weapon.complete_reloading(now, reserve.as_deref_mut());
 
             if !was_armed {
                 audio.queue(AudioPlay {
@@ -67,26 +72,35 @@ fn on_update(
             }
         }
 
-        if actor.actions.is_attacking() && weapon.try_fire(now) {
+        if actor.actions.is_attacking() && weapon.interrupt_reloading() {
+            audio.stop(entity, Weapon::SOUND_RELOADING);
+        }
+
+        weapon.update_selector(actor.actions.is_selecting_fire_mode());
+
+        if weapon.try_fire(now, actor.actions.is_attacking()) {
             let rotation = transform.rotation.angle_z();
             let offset = Vec2::from_length(Weapon::BARREL_LENGTH, rotation);
             let position = transform.translation.truncate() + offset;
 
-            audio.queue(AudioPlay {
-                path: format!("weapons/{}/shot", weapon.config.name).into(),
+            let sound = AudioPlay {
+                path: format!("{}/shot", weapon.config.get_assets_path()).into(),
                 volume: 1.0,
                 falloff: AudioPlay::FALLOFF_LONGER,
                 source: Some(position),
                 ..AudioPlay::DEFAULT
-            });
+            };
+
+            noises.write_batch(Noise::from_audio(&sound, Some(entity)));
+            audio.queue(sound);
 
             if has_shells(&weapon) && weapon.config.has_bolt {
                 commands.queue(ShellParticleSpawn(entity));
             }
 
             for _ in 0..weapon.config.projectile.fragments {
-                let deviation = weapon.config.generate_deviation(&mut data.rng);
-                let velocity = weapon.config.generate_velocity(&mut data.rng);
+                let deviation = weapon.config.generate_deviation(&mut rng);
+                let velocity = weapon.config.generate_velocity(&mut rng);
 
                 commands.queue(ProjectileSpawn {
                     config: weapon.config.projectile,
@@ -97,7 +111,7 @@ fn on_update(
             }
 
             let recoil_push = transform.rotation.as_vec() * -weapon.get_recoil();
-            let recoil_spin = if data.rng.random::<bool>() {
+            let recoil_spin = if rng.random::<bool>() {
                 actor.config.recoil_factor / actor.skill
             } else {
                 actor.config.recoil_factor / -actor.skill
@@ -106,20 +120,26 @@ fn on_update(
             hits.add(entity, recoil_push, recoil_spin, true);
         }
 
-        if !weapon.is_reloading() && (!weapon.has_ammo() || actor.actions.is_reloading()) {
+        if !weapon.is_reloading()
+            && (!weapon.has_ammo() || actor.actions.is_reloading() || weapon.is_loading_shells())
+            && weapon.can_reload(reserve.as_deref())
+        {
+            let is_next_shell = weapon.is_loading_shells();
             let reloading_duration = weapon.config.reloading_time.div_f32(actor.skill);
             weapon.reload(now, reloading_duration);
             audio.queue(AudioPlay {
-                path: "sounds/reloading".into(),
+                path: Weapon::SOUND_RELOADING.into(),
                 volume: 0.4,
                 falloff: AudioPlay::FALLOFF_SHORTER,
                 source: Some(transform.translation.xy()),
-                duration: reloading_duration, // TODO: stop if weapon will be changed earlier
+                duration: reloading_duration,
+                owner: Some(entity),
                 ..AudioPlay::DEFAULT
             });
 
-            if has_shells(&weapon) && !weapon.config.has_bolt {
-                for _ in 0..weapon.config.ammo_capacity {
+            if has_shells(&weapon) && !weapon.config.has_bolt && !is_next_shell {
+                // only the spent shells are thrown out
+                for _ in weapon.ammo()..weapon.config.ammo_capacity {
                     commands.queue(ShellParticleSpawn(entity));
                 }
             }
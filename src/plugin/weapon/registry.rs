@@ -0,0 +1,73 @@
+use crate::{plugin::WeaponConfig, util::load_definitions};
+use anyhow::{Context, Result};
+use bevy::ecs::resource::Resource;
+use std::path::Path;
+
+const DIRECTORY: &str = "assets/weapons";
+const FILE: &str = "weapon.toml";
+
+/// All the weapons loaded from `assets/weapons/<name>/weapon.toml` files. Configs are leaked
+/// once on start so they can be referenced statically just like the rest of configs
+#[derive(Resource)]
+pub struct WeaponRegistry {
+    weapons: Vec<&'static WeaponConfig>,
+}
+
+impl WeaponRegistry {
+    pub fn load() -> Result<Self> {
+        return Self::load_from(Path::new(DIRECTORY));
+    }
+
+    fn load_from(directory: &Path) -> Result<Self> {
+        let mut weapons = Vec::new();
+
+        for definition in load_definitions(directory, FILE)? {
+            let mut weapon = definition.parse::<WeaponConfig>()?;
+            weapon.name = definition.name;
+            weapon
+                .validate()
+                .with_context(|| format!("Invalid weapon {}", definition.path.display()))?;
+            weapons.push(&*Box::leak(Box::new(weapon)));
+        }
+
+        weapons.sort_by(|a, b| a.level.cmp(&b.level).then_with(|| a.name.cmp(&b.name)));
+
+        return Ok(Self { weapons });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&'static WeaponConfig> {
+        let weapon = self.weapons.iter().find(|w| w.name == name).copied();
+
+        if weapon.is_none() {
+            log::warn!("Weapon `{}` not found", name);
+        }
+
+        return weapon;
+    }
+
+    /// All weapons sorted by level
+    pub fn all(&self) -> &[&'static WeaponConfig] {
+        return &self.weapons;
+    }
+
+    /// Weapons which may be given to the player, i.e. these of a non-zero level
+    pub fn available(&self) -> impl Iterator<Item = &'static WeaponConfig> + '_ {
+        return self.weapons.iter().filter(|w| w.level > 0).copied();
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_assets() {
+        let registry = WeaponRegistry::load().unwrap();
+
+        assert!(registry.get("PM").is_some());
+        assert!(registry.get("PM broken").is_some());
+        assert!(registry.available().all(|w| w.level > 0));
+        assert!(registry.get("Unknown").is_none());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::plugin::{ProjectileConfig, WeaponConfig};
+use bevy::ecs::component::Component;
+
+/// Ammo an actor carries to reload its weapons with, by projectile. Actors without it, like bots,
+/// reload from nothing
+#[derive(Component, Default)]
+pub struct AmmoReserve {
+    ammo: Vec<(&'static ProjectileConfig, u16)>,
+}
+
+impl AmmoReserve {
+    /// Spare magazines which come along with a new weapon
+    pub const MAGAZINES_WITH_WEAPON: u16 = 3;
+
+    pub fn all(&self) -> &[(&'static ProjectileConfig, u16)] {
+        return &self.ammo;
+    }
+
+    pub fn get(&self, projectile: &ProjectileConfig) -> u16 {
+        return self
+            .ammo
+            .iter()
+            .find(|(p, _)| std::ptr::eq(*p, projectile))
+            .map_or(0, |(_, n)| *n);
+    }
+
+    pub fn add(&mut self, projectile: &'static ProjectileConfig, ammo: u16) {
+        let total = self.get(projectile).saturating_add(ammo);
+        self.set(projectile, total);
+    }
+
+    pub fn set(&mut self, projectile: &'static ProjectileConfig, ammo: u16) {
+        if let Some((_, n)) = self
+            .ammo
+            .iter_mut()
+            .find(|(p, _)| std::ptr::eq(*p, projectile))
+        {
+            *n = ammo;
+        } else if ammo > 0 {
+            self.ammo.push((projectile, ammo));
+        }
+    }
+
+    /// Spare magazines for the weapon
+    pub fn add_magazines(&mut self, weapon: &'static WeaponConfig, magazines: u16) {
+        let ammo = u16::from(weapon.ammo_capacity).saturating_mul(magazines);
+        self.add(weapon.projectile, ammo);
+    }
+
+    /// Takes as much as there is up to the limit. Returns the taken amount
+    pub fn take(&mut self, projectile: &ProjectileConfig, limit: u8) -> u8 {
+        let Some((_, n)) = self
+            .ammo
+            .iter_mut()
+            .find(|(p, _)| std::ptr::eq(*p, projectile))
+        else {
+            return 0;
+        };
+
+        let taken = u8::try_from(*n).unwrap_or(u8::MAX).min(limit);
+        *n -= u16::from(taken);
+        return taken;
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take() {
+        let pistol = ProjectileConfig::find("9x18").unwrap();
+        let rifle = ProjectileConfig::find("5.45x39").unwrap();
+
+        let mut reserve = AmmoReserve::default();
+        reserve.add(pistol, 300);
+        reserve.add(pistol, 10);
+
+        assert_eq!(reserve.take(pistol, 30), 30);
+        assert_eq!(reserve.take(rifle, 30), 0);
+        assert_eq!(reserve.get(pistol), 280);
+        assert_eq!(reserve.take(pistol, u8::MAX), u8::MAX);
+        assert_eq!(reserve.take(pistol, u8::MAX), 25);
+        assert_eq!(reserve.get(pistol), 0);
+    }
+}
@@ -1,19 +1,48 @@
 use bevy::{
-    asset::{AssetServer, Assets, Handle, LoadedFolder},
+    asset::{
+        io::{AssetSourceId, ErasedAssetReader},
+        AssetServer, Assets, Handle, UntypedHandle,
+    },
     ecs::resource::Resource,
     mesh::Mesh,
     prelude::Rectangle,
+    tasks::{block_on, futures_lite::StreamExt},
 };
+use std::path::{Path, PathBuf};
 
 #[derive(Default, Resource)]
 pub struct AssetStorage {
-    assets: Option<Handle<LoadedFolder>>,
+    assets: Option<Vec<UntypedHandle>>,
     dummy_mesh: Handle<Mesh>,
 }
 
 impl AssetStorage {
+    /// Loads every asset which has a loader. `AssetServer::load_folder` isn't used since a single
+    /// file without a loader, like a data file or a license, fails the whole folder
     pub fn load(&mut self, asset_server: &AssetServer, meshes: &mut Assets<Mesh>) {
-        self.assets = Some(asset_server.load_folder("."));
+        let mut assets = Vec::new();
+
+        match asset_server.get_source(AssetSourceId::Default) {
+            Ok(source) => {
+                let mut files = Vec::new();
+                block_on(find_files(
+                    asset_server,
+                    source.reader(),
+                    Path::new(""),
+                    &mut files,
+                ));
+
+                for path in files {
+                    assets.push(asset_server.load_builder().load_untyped(path).untyped());
+                }
+            }
+            Err(error) => {
+                log::error!("Failed to load assets: {error}");
+            }
+        }
+
+        log::debug!("Assets to load: {}", assets.len());
+        self.assets = Some(assets);
         self.dummy_mesh = meshes.add(Mesh::from(Rectangle::default()));
     }
 
@@ -22,13 +51,42 @@ impl AssetStorage {
     }
 
     pub fn is_loaded(&self, asset_server: &AssetServer) -> bool {
-        return self
-            .assets
-            .as_ref()
-            .is_some_and(|h| asset_server.is_loaded_with_dependencies(h.id()));
+        return self.assets.as_ref().is_some_and(|assets| {
+            return assets
+                .iter()
+                .all(|h| asset_server.is_loaded_with_dependencies(h.id()));
+        });
     }
 
     pub fn dummy_mesh(&self) -> &Handle<Mesh> {
         return &self.dummy_mesh;
     }
 }
+
+/// Recursively finds files which have a loader
+async fn find_files(
+    asset_server: &AssetServer,
+    reader: &dyn ErasedAssetReader,
+    directory: &Path,
+    files: &mut Vec<PathBuf>,
+) {
+    let mut paths = match reader.read_directory(directory).await {
+        Ok(paths) => paths,
+        Err(error) => {
+            log::error!("Failed to read {}: {:?}", directory.display(), error);
+            return;
+        }
+    };
+
+    while let Some(path) = paths.next().await {
+        if reader.is_directory(&path).await.unwrap_or(false) {
+            Box::pin(find_files(asset_server, reader, &path, files)).await;
+        } else if asset_server
+            .get_path_asset_loader(path.clone())
+            .await
+            .is_ok()
+        {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use bevy::input::{gamepad::GamepadButton, keyboard::KeyCode, mouse::MouseButton};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// Names of bindings in the settings, which are also shown in hints
+const KEYS: &[(&str, KeyCode)] = &[
+    ("A", KeyCode::KeyA),
+    ("B", KeyCode::KeyB),
+    ("C", KeyCode::KeyC),
+    ("D", KeyCode::KeyD),
+    ("E", KeyCode::KeyE),
+    ("F", KeyCode::KeyF),
+    ("G", KeyCode::KeyG),
+    ("H", KeyCode::KeyH),
+    ("I", KeyCode::KeyI),
+    ("J", KeyCode::KeyJ),
+    ("K", KeyCode::KeyK),
+    ("L", KeyCode::KeyL),
+    ("M", KeyCode::KeyM),
+    ("N", KeyCode::KeyN),
+    ("O", KeyCode::KeyO),
+    ("P", KeyCode::KeyP),
+    ("Q", KeyCode::KeyQ),
+    ("R", KeyCode::KeyR),
+    ("S", KeyCode::KeyS),
+    ("T", KeyCode::KeyT),
+    ("U", KeyCode::KeyU),
+    ("V", KeyCode::KeyV),
+    ("W", KeyCode::KeyW),
+    ("X", KeyCode::KeyX),
+    ("Y", KeyCode::KeyY),
+    ("Z", KeyCode::KeyZ),
+    ("0", KeyCode::Digit0),
+    ("1", KeyCode::Digit1),
+    ("2", KeyCode::Digit2),
+    ("3", KeyCode::Digit3),
+    ("4", KeyCode::Digit4),
+    ("5", KeyCode::Digit5),
+    ("6", KeyCode::Digit6),
+    ("7", KeyCode::Digit7),
+    ("8", KeyCode::Digit8),
+    ("9", KeyCode::Digit9),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+    ("ESC", KeyCode::Escape),
+    ("TAB", KeyCode::Tab),
+    ("SPACE", KeyCode::Space),
+    ("ENTER", KeyCode::Enter),
+    ("BACKSPACE", KeyCode::Backspace),
+    ("CAPSLOCK", KeyCode::CapsLock),
+    ("SHIFT", KeyCode::ShiftLeft),
+    ("RSHIFT", KeyCode::ShiftRight),
+    ("CTRL", KeyCode::ControlLeft),
+    ("RCTRL", KeyCode::ControlRight),
+    ("ALT", KeyCode::AltLeft),
+    ("RALT", KeyCode::AltRight),
+    ("UP", KeyCode::ArrowUp),
+    ("DOWN", KeyCode::ArrowDown),
+    ("LEFT", KeyCode::ArrowLeft),
+    ("RIGHT", KeyCode::ArrowRight),
+    ("-", KeyCode::Minus),
+    ("=", KeyCode::Equal),
+];
+
+const MOUSE_BUTTONS: &[(&str, MouseButton)] = &[
+    ("LMB", MouseButton::Left),
+    ("RMB", MouseButton::Right),
+    ("MMB", MouseButton::Middle),
+    ("MOUSE4", MouseButton::Back),
+    ("MOUSE5", MouseButton::Forward),
+];
+
+const WHEEL: &[(&str, Wheel)] = &[("WHEEL_UP", Wheel::Up), ("WHEEL_DOWN", Wheel::Down)];
+
+const GAMEPAD_BUTTONS: &[(&str, GamepadButton)] = &[
+    ("PAD_A", GamepadButton::South),
+    ("PAD_B", GamepadButton::East),
+    ("PAD_X", GamepadButton::West),
+    ("PAD_Y", GamepadButton::North),
+    ("PAD_LB", GamepadButton::LeftTrigger),
+    ("PAD_RB", GamepadButton::RightTrigger),
+    ("PAD_LT", GamepadButton::LeftTrigger2),
+    ("PAD_RT", GamepadButton::RightTrigger2),
+    ("PAD_LS", GamepadButton::LeftThumb),
+    ("PAD_RS", GamepadButton::RightThumb),
+    ("PAD_UP", GamepadButton::DPadUp),
+    ("PAD_DOWN", GamepadButton::DPadDown),
+    ("PAD_LEFT", GamepadButton::DPadLeft),
+    ("PAD_RIGHT", GamepadButton::DPadRight),
+    ("PAD_SELECT", GamepadButton::Select),
+    ("PAD_START", GamepadButton::Start),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Control {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    Sprint,
+    Attack,
+    Reload,
+    Aim,
+    /// Switches between semi, burst and full-auto fire, if the weapon has them
+    FireMode,
+    WeaponPrimary,
+    WeaponSecondary,
+    WeaponMelee,
+    WeaponNext,
+    WeaponPrevious,
+    /// Drops the weapon in hands
+    WeaponDrop,
+    /// Picks up the weapon underfoot even if it takes the place of a carried one
+    WeaponPickUp,
+    ZoomIn,
+    ZoomOut,
+    /// Orders allied bots to follow the player
+    SquadFollow,
+    /// Orders allied bots to stay where they are
+    SquadHold,
+    /// Orders allied bots to attack where the player aims
+    SquadAttack,
+    /// Orders allied bots to come back to the player at once
+    SquadRegroup,
+    ReleaseCursor,
+    Fullscreen,
+    Save,
+    FastForward,
+    Exit,
+}
+
+/// A button of any device. Written in the settings by its name, like `"SHIFT"`, `"RMB"` or
+/// `"PAD_X"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Counts as pressed during a frame the mouse wheel is scrolled in the direction
+    Wheel(Wheel),
+    Gamepad(GamepadButton),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wheel {
+    Up,
+    Down,
+}
+
+/// Bindings of each control. The ones which aren't set in the settings keep their defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<Control, Vec<Binding>>")]
+pub struct Bindings(BTreeMap<Control, Vec<Binding>>);
+
+impl Binding {
+    pub fn name(self) -> &'static str {
+        let name = match self {
+            Self::Key(key) => find_name(KEYS, key),
+            Self::Mouse(button) => find_name(MOUSE_BUTTONS, button),
+            Self::Wheel(direction) => find_name(WHEEL, direction),
+            Self::Gamepad(button) => find_name(GAMEPAD_BUTTONS, button),
+        };
+
+        return name.unwrap_or("?");
+    }
+
+    pub fn is_gamepad(self) -> bool {
+        return matches!(self, Self::Gamepad(_));
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(self.name());
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        let name = name.to_uppercase();
+
+        if let Some(key) = find_button(KEYS, &name) {
+            return Ok(Self::Key(key));
+        }
+
+        if let Some(button) = find_button(MOUSE_BUTTONS, &name) {
+            return Ok(Self::Mouse(button));
+        }
+
+        if let Some(direction) = find_button(WHEEL, &name) {
+            return Ok(Self::Wheel(direction));
+        }
+
+        if let Some(button) = find_button(GAMEPAD_BUTTONS, &name) {
+            return Ok(Self::Gamepad(button));
+        }
+
+        return Err(format!("Unknown binding `{}`", name));
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        return binding.name().to_string();
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, control: Control) -> &[Binding] {
+        return self.0.get(&control).map_or(&[], Vec::as_slice);
+    }
+
+    /// Bound buttons in brackets to show in hints, like `[R]`. Gamepad ones are shown only if
+    /// requested, since there may be no gamepad at all
+    pub fn hint(&self, control: Control, with_gamepad: bool) -> String {
+        let hint = self
+            .get(control)
+            .iter()
+            .filter(|b| with_gamepad || !b.is_gamepad())
+            .map(|b| format!("[{}]", b))
+            .collect::<Vec<_>>()
+            .join(" / ");
+
+        if hint.is_empty() {
+            return "[unbound]".to_string();
+        } else {
+            return hint;
+        }
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use self::Binding::{Gamepad, Key, Mouse};
+
+        return Self(BTreeMap::from([
+            (Control::MoveForward, vec![Key(KeyCode::KeyW)]),
+            (Control::MoveBackward, vec![Key(KeyCode::KeyS)]),
+            (Control::MoveLeft, vec![Key(KeyCode::KeyA)]),
+            (Control::MoveRight, vec![Key(KeyCode::KeyD)]),
+            (
+                Control::Sprint,
+                vec![Key(KeyCode::ShiftLeft), Gamepad(GamepadButton::LeftThumb)],
+            ),
+            (
+                Control::Attack,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Gamepad(GamepadButton::RightTrigger2),
+                ],
+            ),
+            (
+                Control::Reload,
+                vec![Key(KeyCode::KeyR), Gamepad(GamepadButton::West)],
+            ),
+            (
+                Control::Aim,
+                vec![
+                    Mouse(MouseButton::Right),
+                    Gamepad(GamepadButton::LeftTrigger2),
+                ],
+            ),
+            (
+                Control::FireMode,
+                vec![Key(KeyCode::KeyB), Gamepad(GamepadButton::South)],
+            ),
+            (Control::WeaponPrimary, vec![Key(KeyCode::Digit1)]),
+            (Control::WeaponSecondary, vec![Key(KeyCode::Digit2)]),
+            (Control::WeaponMelee, vec![Key(KeyCode::Digit3)]),
+            (
+                Control::WeaponNext,
+                vec![
+                    Binding::Wheel(Wheel::Down),
+                    Gamepad(GamepadButton::RightTrigger),
+                ],
+            ),
+            (
+                Control::WeaponPrevious,
+                vec![
+                    Binding::Wheel(Wheel::Up),
+                    Gamepad(GamepadButton::LeftTrigger),
+                ],
+            ),
+            (
+                Control::WeaponDrop,
+                vec![Key(KeyCode::KeyQ), Gamepad(GamepadButton::RightThumb)],
+            ),
+            (
+                Control::WeaponPickUp,
+                vec![Key(KeyCode::KeyE), Gamepad(GamepadButton::Select)],
+            ),
+            (
+                Control::ZoomIn,
+                vec![Key(KeyCode::Equal), Gamepad(GamepadButton::DPadUp)],
+            ),
+            (
+                Control::ZoomOut,
+                vec![Key(KeyCode::Minus), Gamepad(GamepadButton::DPadDown)],
+            ),
+            (
+                Control::SquadFollow,
+                vec![Key(KeyCode::KeyZ), Gamepad(GamepadButton::DPadLeft)],
+            ),
+            (
+                Control::SquadHold,
+                vec![Key(KeyCode::KeyX), Gamepad(GamepadButton::DPadRight)],
+            ),
+            (
+                Control::SquadAttack,
+                vec![Key(KeyCode::KeyC), Gamepad(GamepadButton::North)],
+            ),
+            (
+                Control::SquadRegroup,
+                vec![Key(KeyCode::KeyV), Gamepad(GamepadButton::East)],
+            ),
+            (Control::ReleaseCursor, vec![Key(KeyCode::Tab)]),
+            (Control::Fullscreen, vec![Key(KeyCode::F11)]),
+            (Control::Save, vec![Key(KeyCode::F5)]),
+            (Control::FastForward, vec![Key(KeyCode::KeyF)]),
+            (Control::Exit, vec![Key(KeyCode::Escape)]),
+        ]));
+    }
+}
+
+impl From<BTreeMap<Control, Vec<Binding>>> for Bindings {
+    fn from(bindings: BTreeMap<Control, Vec<Binding>>) -> Self {
+        let mut merged = Self::default();
+        merged.0.extend(bindings);
+        return merged;
+    }
+}
+
+fn find_name<T: PartialEq>(table: &[(&'static str, T)], button: T) -> Option<&'static str> {
+    return table.iter().find(|(_, b)| *b == button).map(|(n, _)| *n);
+}
+
+fn find_button<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    return table.iter().find(|(n, _)| *n == name).map(|(_, b)| *b);
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        for name in ["W", "shift", "RMB", "wheel_up", "pad_x"] {
+            let binding = Binding::try_from(name.to_string());
+            assert_eq!(binding.map(String::from), Ok(name.to_uppercase()));
+        }
+
+        assert!(Binding::try_from("Nope".to_string()).is_err());
+    }
+
+    #[test]
+    fn defaults_kept() {
+        let bindings = toml::from_str::<Bindings>("reload = [\"E\"]\nsprint = []").unwrap();
+
+        assert_eq!(
+            bindings.get(Control::Reload),
+            &[Binding::Key(KeyCode::KeyE)]
+        );
+        assert_eq!(bindings.hint(Control::Sprint, false), "[unbound]");
+        assert_eq!(bindings.hint(Control::Aim, false), "[RMB]");
+        assert_eq!(bindings.hint(Control::Aim, true), "[RMB] / [PAD_LT]");
+    }
+}
@@ -1,5 +1,7 @@
 mod asset_storage;
+mod bindings;
 mod hit;
+mod rng;
 mod settings;
 
-pub use self::{asset_storage::*, hit::*, settings::*};
+pub use self::{asset_storage::*, bindings::*, hit::*, rng::*, settings::*};
@@ -0,0 +1,55 @@
+use bevy::ecs::resource::Resource;
+use rand::{RngExt, SeedableRng};
+use rand_pcg::Pcg32;
+use std::ops::{Deref, DerefMut};
+
+/// The only source of randomness for the game logic. Since it's seeded once, the same seed and
+/// the same inputs give the same game
+#[derive(Resource)]
+pub struct RngResource {
+    seed: u64,
+    rng: Pcg32,
+}
+
+impl RngResource {
+    pub fn new(seed: u64) -> Self {
+        return Self {
+            seed,
+            rng: Pcg32::seed_from_u64(seed),
+        };
+    }
+
+    /// Uses the given seed, or a random one if it's zero
+    pub fn new_or_random(mut seed: u64) -> Self {
+        while seed == 0 {
+            seed = rand::rng().random();
+        }
+
+        log::info!("Random seed: {}", seed);
+        return Self::new(seed);
+    }
+
+    pub fn seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    /// Creates a separate generator seeded by this one. It's useful for things like sounds which
+    /// take random numbers at their own rate, so they don't shift the sequence of the game logic
+    pub fn fork(&mut self) -> Pcg32 {
+        return Pcg32::from_rng(&mut self.rng);
+    }
+}
+
+impl Deref for RngResource {
+    type Target = Pcg32;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.rng;
+    }
+}
+
+impl DerefMut for RngResource {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.rng;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::resource::Bindings;
 use anyhow::{Context, Result};
 use bevy::{
     ecs::resource::Resource,
@@ -17,11 +18,19 @@ pub struct Settings {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameSettings {
     pub scenario: ScenarioSettings,
+    /// Name of a campaign file from `assets/campaigns` used by the waves scenario
+    pub campaign: String,
     /// 0.8 - easy, 1.0 - medium, 1.2 - hard
     pub difficulty: f32,
     pub level: u8,
+    /// Number of local players. The first one plays with keyboard and mouse, or a gamepad left
+    /// spare by the rest, who play with gamepads
+    pub players: u8,
+    /// Seed of the game randomness. Zero means a random seed on each start
+    pub seed: u64,
     pub debug: bool,
 }
 
@@ -29,8 +38,11 @@ impl Default for GameSettings {
     fn default() -> Self {
         return Self {
             scenario: ScenarioSettings::Waves,
+            campaign: "default".to_string(),
             difficulty: 1.0,
             level: 1,
+            players: 1,
+            seed: 0,
             debug: false,
         };
     }
@@ -85,14 +97,26 @@ impl Default for AudioSettings {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ControlsSettings {
     pub mouse_sensitivity: f32,
+    /// Stick tilt from 0.0 to 1.0 which is ignored, since worn sticks don't center exactly
+    pub gamepad_dead_zone: f32,
+    /// How far the aim goes at the full stick tilt. 1.0 - to the edge of the view
+    pub gamepad_sensitivity: f32,
+    /// How deep a trigger is pressed to fire, from 0.0 to 1.0
+    pub gamepad_trigger_threshold: f32,
+    pub bindings: Bindings,
 }
 
 impl Default for ControlsSettings {
     fn default() -> Self {
         return Self {
             mouse_sensitivity: 0.003,
+            gamepad_dead_zone: 0.2,
+            gamepad_sensitivity: 1.0,
+            gamepad_trigger_threshold: 0.5,
+            bindings: Bindings::default(),
         };
     }
 }
@@ -173,4 +197,15 @@ mod tests {
         let actual = format!("{:?}", Settings::load().unwrap());
         assert_eq!(default, actual);
     }
+
+    #[test]
+    fn game_without_new_keys() {
+        let encoded = "[game]\nscenario = \"test\"\ndifficulty = 1.2\nlevel = 3\ndebug = true\n";
+        let game = toml::from_str::<Settings>(encoded).unwrap().game;
+        assert_eq!(game.scenario, ScenarioSettings::Test);
+        assert_eq!(game.level, 3);
+        assert_eq!(game.campaign, GameSettings::default().campaign);
+        assert_eq!(game.players, 1);
+        assert_eq!(game.seed, 0);
+    }
 }
@@ -0,0 +1,33 @@
+use anyhow::{ensure, Result};
+
+/// Reads values of a binary format one by one, failing instead of panicking on a short input
+pub struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        return Self(data);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn take_slice(&mut self, length: usize) -> Result<&'a [u8]> {
+        ensure!(self.0.len() >= length, "Unexpected end of data");
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        return Ok(taken);
+    }
+
+    pub fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        return Ok(self.take_slice(N)?.try_into()?);
+    }
+
+    pub fn take_u8(&mut self) -> Result<u8> {
+        return Ok(self.take::<1>()?[0]);
+    }
+
+    pub fn take_f32(&mut self) -> Result<f32> {
+        return Ok(f32::from_le_bytes(self.take()?));
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+const KEY_BASE: &str = "base";
+
+/// A definition loaded from `<directory>/<name>/<file>` data file. Its name is the name of the
+/// directory it's placed in, so it matches the rest of the assets (images, sounds) located nearby
+pub struct Definition {
+    pub name: String,
+    pub path: PathBuf,
+    pub table: Table,
+}
+
+impl Definition {
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T> {
+        return Value::Table(self.table.clone())
+            .try_into()
+            .with_context(|| format!("Failed to parse {}", self.path.display()));
+    }
+}
+
+/// Loads all the definitions from `<directory>/*/<file>` files sorted by name. Subdirectories
+/// without the file are ignored.
+///
+/// A definition may inherit values of another one by naming it in the `base` key, then it only
+/// needs to specify the values which differ. Nested tables are merged key by key
+pub fn load_definitions(directory: &Path, file: &str) -> Result<Vec<Definition>> {
+    let mut raw = Vec::new();
+
+    for entry in std::fs::read_dir(directory)
+        .with_context(|| format!("Failed to read directory {}", directory.display()))?
+    {
+        let entry = entry?;
+        let path = entry.path().join(file);
+
+        if !path.is_file() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let encoded = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let table = toml::from_str::<Table>(&encoded)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        raw.push(Definition { name, path, table });
+    }
+
+    raw.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut resolved = Vec::with_capacity(raw.len());
+
+    for definition in &raw {
+        resolved.push(Definition {
+            name: definition.name.clone(),
+            path: definition.path.clone(),
+            table: resolve(definition, &raw, &mut Vec::new())?,
+        });
+    }
+
+    return Ok(resolved);
+}
+
+fn resolve<'a>(
+    definition: &'a Definition,
+    all: &'a [Definition],
+    chain: &mut Vec<&'a str>,
+) -> Result<Table> {
+    if chain.contains(&definition.name.as_str()) {
+        chain.push(&definition.name);
+        bail!("Cyclic inheritance: {}", chain.join(" -> "));
+    }
+
+    chain.push(&definition.name);
+
+    let mut table = definition.table.clone();
+
+    let base = match table.remove(KEY_BASE) {
+        None => return Ok(table),
+        Some(Value::String(base)) => base,
+        Some(_) => {
+            bail!(
+                "Key `{}` must be a string in {}",
+                KEY_BASE,
+                definition.path.display(),
+            );
+        }
+    };
+
+    let Some(base_definition) = all.iter().find(|d| d.name == base) else {
+        bail!("Unknown base `{}` in {}", base, definition.path.display(),);
+    };
+
+    let mut resolved = resolve(base_definition, all, chain)?;
+    merge(&mut resolved, table);
+    return Ok(resolved);
+}
+
+fn merge(base: &mut Table, table: Table) {
+    for (key, value) in table {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(table)) => merge(base, table),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::unwrap_used, reason = "allow unwraps in unit-tests")]
+mod tests {
+    use super::*;
+
+    fn definition(name: &str, encoded: &str) -> Definition {
+        return Definition {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            table: toml::from_str(encoded).unwrap(),
+        };
+    }
+
+    #[test]
+    fn inheritance() {
+        let all = [
+            definition("a", "x = 1\ny = 2\n[t]\nu = 5\nv = 6"),
+            definition("b", "base = \"a\"\ny = 3\n[t]\nv = 7"),
+            definition("c", "base = \"b\"\nz = 4"),
+        ];
+
+        let c = resolve(&all[2], &all, &mut Vec::new()).unwrap();
+        assert_eq!(c.get("x").and_then(Value::as_integer), Some(1));
+        assert_eq!(c.get("y").and_then(Value::as_integer), Some(3));
+        assert_eq!(c.get("z").and_then(Value::as_integer), Some(4));
+
+        let t = c.get("t").and_then(Value::as_table).unwrap();
+        assert_eq!(t.get("u").and_then(Value::as_integer), Some(5));
+        assert_eq!(t.get("v").and_then(Value::as_integer), Some(7));
+        assert!(c.get(KEY_BASE).is_none());
+    }
+
+    #[test]
+    fn inheritance_errors() {
+        let all = [
+            definition("a", "base = \"b\""),
+            definition("b", "base = \"a\""),
+            definition("c", "base = \"d\""),
+        ];
+
+        let error = resolve(&all[0], &all, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.to_string(), "Cyclic inheritance: a -> b -> a");
+
+        let error = resolve(&all[2], &all, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown base `d` in c");
+    }
+}
@@ -1,8 +1,8 @@
-use crate::AppState;
+use crate::{plugin::simulation::AuthoritySystems, AppState};
 use bevy::{
     app::App,
     ecs::{schedule::IntoScheduleConfigs, system::ScheduleSystem},
-    prelude::Update,
+    prelude::{FixedUpdate, Update},
     state::{condition::in_state, state::OnEnter},
 };
 
@@ -13,6 +13,14 @@ pub trait AppExt {
         system: impl IntoScheduleConfigs<ScheduleSystem, M>,
     ) -> &mut Self;
 
+    /// Adds a system of the game simulation which runs with a fixed timestep, so its result
+    /// doesn't depend on the frame rate. It doesn't run on a network client
+    fn add_state_system_fixed<M>(
+        &mut self,
+        state: AppState,
+        system: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+
     fn add_state_system_enter<M>(
         &mut self,
         state: AppState,
@@ -29,6 +37,17 @@ impl AppExt for App {
         return self.add_systems(Update, system.run_if(in_state(state)));
     }
 
+    fn add_state_system_fixed<M>(
+        &mut self,
+        state: AppState,
+        system: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        return self.add_systems(
+            FixedUpdate,
+            system.in_set(AuthoritySystems).run_if(in_state(state)),
+        );
+    }
+
     fn add_state_system_enter<M>(
         &mut self,
         state: AppState,
@@ -1,8 +1,15 @@
+#[cfg_attr(
+    not(feature = "bench"),
+    expect(dead_code, reason = "used only with the `bench` feature")
+)]
 pub mod bench;
+mod byte_reader;
+mod definition;
 mod envelope;
 pub mod ext;
 pub mod geometry;
 pub mod math;
+pub mod serde_secs;
 mod smart_string;
 #[cfg(test)]
 pub mod test;
@@ -10,4 +17,6 @@ mod timer;
 pub mod traits;
 mod transform_2d;
 
-pub use self::{envelope::*, smart_string::*, timer::*, transform_2d::*};
+pub use self::{
+    byte_reader::*, definition::*, envelope::*, smart_string::*, timer::*, transform_2d::*,
+};
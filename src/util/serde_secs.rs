@@ -0,0 +1,10 @@
+//! Allows to deserialize `Duration` from a floating-point number of seconds, which is way more
+//! readable in data files than serde's default `{ secs, nanos }` representation
+
+use serde::{de::Error, Deserialize, Deserializer};
+use std::time::Duration;
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let secs = f64::deserialize(deserializer)?;
+    return Duration::try_from_secs_f64(secs).map_err(D::Error::custom);
+}